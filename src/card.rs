@@ -0,0 +1,228 @@
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+use cards::{Cards, ParseCardsError, ParseCardsErrorKind};
+
+pub(crate) const RANKS: [char; 13] = [
+    '2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A'
+];
+pub(crate) const SUITS: [char; 4] = ['C', 'D', 'H', 'S'];
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Rank {
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Queen,
+    King,
+    Ace,
+}
+
+impl Rank {
+    pub const ALL: [Rank; 13] = [
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+        Rank::Ace,
+    ];
+
+    pub fn char(self) -> char {
+        RANKS[self as usize]
+    }
+
+    /// The rank for a character such as 'Q' or 'q'. Ten is 'T'; the two
+    /// character form "10" is handled by the parser.
+    pub fn from_char(c: char) -> Option<Rank> {
+        RANKS
+            .iter()
+            .position(|&r| r == c.to_ascii_uppercase())
+            .map(|i| Rank::ALL[i])
+    }
+}
+
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_char(self.char())
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Suit {
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades,
+}
+
+impl Suit {
+    pub const ALL: [Suit; 4] = [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades];
+
+    pub fn char(self) -> char {
+        SUITS[self as usize]
+    }
+
+    /// The suit for a letter such as 'S' or 's', or a symbol such as '♠'.
+    pub fn from_char(c: char) -> Option<Suit> {
+        match c {
+            '♣' => Some(Suit::Clubs),
+            '♦' => Some(Suit::Diamonds),
+            '♥' => Some(Suit::Hearts),
+            '♠' => Some(Suit::Spades),
+            _ => SUITS
+                .iter()
+                .position(|&s| s == c.to_ascii_uppercase())
+                .map(|i| Suit::ALL[i]),
+        }
+    }
+
+    /// All thirteen cards of this suit.
+    pub fn cards(self) -> Cards {
+        Cards::from_bits(0x1fff << (16 * self as u64)).unwrap()
+    }
+}
+
+impl fmt::Display for Suit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_char(self.char())
+    }
+}
+
+/// A single card, stored as its bit index in `Cards`: 16 times the suit plus
+/// the rank, so each suit occupies its own 16 bit lane.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Card(pub(crate) u8);
+
+impl Card {
+    pub const TWO_CLUBS: Card = Card(0);
+
+    pub fn new(rank: Rank, suit: Suit) -> Card {
+        Card(16 * suit as u8 + rank as u8)
+    }
+
+    pub fn rank(self) -> Rank {
+        Rank::ALL[(self.0 % 16) as usize]
+    }
+
+    pub fn suit_of(self) -> Suit {
+        Suit::ALL[(self.0 / 16) as usize]
+    }
+
+    /// All cards in the same suit as this one.
+    pub fn suit(self) -> Cards {
+        self.suit_of().cards()
+    }
+
+    pub fn as_cards(self) -> Cards {
+        Cards::from_bits(1 << (self.0)).unwrap()
+    }
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_char(self.rank().char())?;
+        f.write_char(self.suit_of().char())?;
+        Ok(())
+    }
+}
+
+impl FromStr for Card {
+    type Err = ParseCardsError;
+
+    fn from_str(s: &str) -> Result<Self, ParseCardsError> {
+        let cards = Cards::parse(s)?;
+        if cards.len() != 1 {
+            let token = s.trim();
+            let offset = token.as_ptr() as usize - s.as_ptr() as usize;
+            return Err(ParseCardsError::new(
+                ParseCardsErrorKind::NotOneCard,
+                token,
+                offset,
+            ));
+        }
+        Ok(cards.max())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_card_parse() {
+        assert_eq!("QS".parse::<Card>().unwrap(), Cards::QUEEN_SPADES.max());
+        assert_eq!(" 10♣".parse::<Card>().unwrap(), Cards::TEN_CLUBS.max());
+        assert_eq!("2c".parse::<Card>().unwrap(), Card::TWO_CLUBS);
+
+        let err = "AKS".parse::<Card>().unwrap_err();
+        assert_eq!(err.kind(), ParseCardsErrorKind::NotOneCard);
+        assert_eq!((err.token(), err.offset()), ("AKS", 0));
+
+        let err = "".parse::<Card>().unwrap_err();
+        assert_eq!(err.kind(), ParseCardsErrorKind::NotOneCard);
+    }
+
+    #[test]
+    fn test_card_round_trip() {
+        for (s, &suit) in Suit::ALL.iter().enumerate() {
+            for (r, &rank) in Rank::ALL.iter().enumerate() {
+                let card = Card::new(rank, suit);
+                assert_eq!(card.rank(), rank);
+                assert_eq!(card.suit_of(), suit);
+                assert_eq!(card.as_cards().max(), card);
+                assert_eq!(card.as_cards().len(), 1);
+                assert!(card.suit().contains(card.as_cards()));
+                assert_eq!(suit.cards(), card.suit());
+
+                let name = card.to_string();
+                assert_eq!(name, format!("{}{}", RANKS[r], SUITS[s]));
+                assert_eq!(name.parse::<Card>().unwrap(), card);
+                assert_eq!(card.as_cards().to_string(), name);
+                assert_eq!(Rank::from_char(name.chars().next().unwrap()), Some(rank));
+                assert_eq!(Suit::from_char(name.chars().last().unwrap()), Some(suit));
+            }
+        }
+    }
+
+    #[test]
+    fn test_card_encoding() {
+        assert_eq!(Card::new(Rank::Two, Suit::Clubs), Card::TWO_CLUBS);
+        assert_eq!(
+            Card::new(Rank::Queen, Suit::Spades),
+            Cards::QUEEN_SPADES.max()
+        );
+        assert_eq!(Card::new(Rank::Ace, Suit::Hearts), Cards::ACE_HEARTS.max());
+        assert_eq!(
+            Card::new(Rank::Jack, Suit::Diamonds),
+            Cards::JACK_DIAMONDS.max()
+        );
+        assert_eq!(Card::new(Rank::Ten, Suit::Clubs), Cards::TEN_CLUBS.max());
+        assert_eq!(Card(49).to_string(), "3S");
+        assert_eq!(Card(49).suit(), Cards::SPADES);
+        let all: Vec<Card> = Suit::ALL
+            .iter()
+            .flat_map(|&suit| Rank::ALL.iter().map(move |&rank| Card::new(rank, suit)))
+            .collect();
+        let mut cards = Cards::empty();
+        for &card in &all {
+            cards |= card;
+        }
+        assert_eq!(cards, Cards::all());
+        assert_eq!(cards.len(), 52);
+    }
+}
@@ -0,0 +1,480 @@
+use std::error::Error;
+use std::fmt::{self, Write};
+use std::iter::{FromIterator, FusedIterator};
+use std::ops::{BitOr, BitOrAssign, Sub, SubAssign};
+use std::str::FromStr;
+
+use card::{Card, Rank, Suit, RANKS, SUITS};
+
+bitflags! {
+    #[derive(Default)]
+    pub struct Cards: u64 {
+        const CHARGEABLE = 0x0400_1000_0200_0100;
+        const NINES = 0x0080_0080_0080_0080;
+        const CLUBS = 0x0000_0000_0000_1fff;
+        const DIAMONDS = 0x0000_0000_1fff_0000;
+        const HEARTS = 0x0000_1fff_0000_0000;
+        const SPADES = 0x1fff_0000_0000_0000;
+        const TWO_CLUBS = 0x0000_0000_0000_0001;
+        const TEN_CLUBS = Self::CLUBS.bits & Self::CHARGEABLE.bits;
+        const JACK_DIAMONDS = Self::DIAMONDS.bits & Self::CHARGEABLE.bits;
+        const ACE_HEARTS = Self::HEARTS.bits & Self::CHARGEABLE.bits;
+        const QUEEN_SPADES = Self::SPADES.bits & Self::CHARGEABLE.bits;
+        const POINTS = Self::HEARTS.bits | Self::QUEEN_SPADES.bits | Self::JACK_DIAMONDS.bits;
+    }
+}
+
+#[allow(clippy::len_without_is_empty)]
+impl Cards {
+    pub fn len(self) -> u32 {
+        self.bits.count_ones()
+    }
+
+    /// Iterates over the cards in ascending order, clubs first.
+    pub fn iter(self) -> CardsIter {
+        CardsIter { bits: self.bits }
+    }
+
+    pub fn max(self) -> Card {
+        Card(63 - self.bits.leading_zeros() as u8)
+    }
+
+    /// The lowest card, clubs first; the set must not be empty.
+    pub fn min(self) -> Card {
+        debug_assert!(!self.is_empty(), "no minimum of an empty set");
+        Card(self.bits.trailing_zeros() as u8)
+    }
+
+    pub fn in_suit(self, suit: Suit) -> Self {
+        self & suit.cards()
+    }
+
+    /// The number of cards held in each suit, indexed by `Suit`.
+    pub fn suit_lengths(self) -> [u32; 4] {
+        let bits = self.bits;
+        [
+            (bits & Cards::CLUBS.bits).count_ones(),
+            (bits & Cards::DIAMONDS.bits).count_ones(),
+            (bits & Cards::HEARTS.bits).count_ones(),
+            (bits & Cards::SPADES.bits).count_ones(),
+        ]
+    }
+
+    /// The cards in the same suit as `card` that rank higher than it.
+    pub fn above(self, card: Card) -> Self {
+        Cards::from_bits_truncate(self.bits & card.suit().bits & (!0 << card.0 << 1))
+    }
+
+    /// The cards in the same suit as `card` that rank lower than it.
+    pub fn below(self, card: Card) -> Self {
+        Cards::from_bits_truncate(self.bits & card.suit().bits & ((1 << card.0) - 1))
+    }
+
+    /// Every card of each suit in which this set holds no cards.
+    pub fn void_suits(self) -> Self {
+        // Adding 0x1fff to a suit carries into bit 13 exactly when the suit
+        // is non-empty, and the carry never reaches the next suit.
+        let held = ((self.bits + 0x1fff_1fff_1fff_1fff) >> 13) & 0x0001_0001_0001_0001;
+        Cards::from_bits_truncate((held ^ 0x0001_0001_0001_0001) * 0x1fff)
+    }
+
+    pub fn suit(self) -> Self {
+        for suit in &[Cards::SPADES, Cards::HEARTS, Cards::DIAMONDS, Cards::CLUBS] {
+            if self.intersects(*suit) {
+                return *suit;
+            }
+        }
+        Cards::empty()
+    }
+
+    /// Parses groups of ranks followed by a suit, such as "AQT5S 10♥ j3d".
+    ///
+    /// Ranks and suits are case-insensitive, "10" may be used for the ten,
+    /// and suits may be given as letters or as ♣♦♥♠. The empty string parses
+    /// as the empty set.
+    pub fn parse(s: &str) -> Result<Self, ParseCardsError> {
+        let mut cards = Cards::empty();
+        for group in s.split_whitespace() {
+            let offset = group.as_ptr() as usize - s.as_ptr() as usize;
+            cards |= parse_group(group, offset)?;
+        }
+        Ok(cards)
+    }
+}
+
+impl FromStr for Cards {
+    type Err = ParseCardsError;
+
+    fn from_str(s: &str) -> Result<Self, ParseCardsError> {
+        Cards::parse(s)
+    }
+}
+
+/// Parses a single group of ranks followed by a suit, where `offset` is the
+/// position of the group within the full input.
+pub(crate) fn parse_group(group: &str, offset: usize) -> Result<Cards, ParseCardsError> {
+    let (suit_at, suit) = group.char_indices().next_back().unwrap();
+    let suit = match Suit::from_char(suit) {
+        Some(suit) => suit,
+        None => {
+            return Err(ParseCardsError::new(
+                ParseCardsErrorKind::InvalidSuit,
+                &group[suit_at..],
+                offset + suit_at,
+            ))
+        }
+    };
+    if suit_at == 0 {
+        return Err(ParseCardsError::new(
+            ParseCardsErrorKind::MissingRank,
+            group,
+            offset,
+        ));
+    }
+    let mut cards = Cards::empty();
+    let mut chars = group[..suit_at].char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let rank = if c == '1' && chars.peek().map(|&(_, c)| c) == Some('0') {
+            chars.next();
+            Some(Rank::Ten)
+        } else {
+            Rank::from_char(c)
+        };
+        match rank {
+            Some(rank) => cards |= Card::new(rank, suit),
+            None => {
+                return Err(ParseCardsError::new(
+                    ParseCardsErrorKind::InvalidRank,
+                    &group[i..i + c.len_utf8()],
+                    offset + i,
+                ))
+            }
+        }
+    }
+    Ok(cards)
+}
+
+/// The reason some input could not be parsed as cards.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ParseCardsErrorKind {
+    /// A character before the suit is not a rank
+    InvalidRank,
+    /// A group of cards does not end in a suit
+    InvalidSuit,
+    /// A suit has no ranks before it
+    MissingRank,
+    /// Exactly one card was expected
+    NotOneCard,
+}
+
+/// An error naming the token that could not be parsed and its byte offset
+/// within the input.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ParseCardsError {
+    kind: ParseCardsErrorKind,
+    token: String,
+    offset: usize,
+}
+
+impl ParseCardsError {
+    pub(crate) fn new(kind: ParseCardsErrorKind, token: &str, offset: usize) -> Self {
+        ParseCardsError {
+            kind,
+            token: token.to_string(),
+            offset,
+        }
+    }
+
+    pub fn kind(&self) -> ParseCardsErrorKind {
+        self.kind
+    }
+
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl fmt::Display for ParseCardsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let what = match self.kind {
+            ParseCardsErrorKind::InvalidRank => "invalid rank",
+            ParseCardsErrorKind::InvalidSuit => "invalid suit",
+            ParseCardsErrorKind::MissingRank => "no ranks before suit in",
+            ParseCardsErrorKind::NotOneCard => "expected one card, found",
+        };
+        write!(f, "{} {:?} at offset {}", what, self.token, self.offset)
+    }
+}
+
+impl Error for ParseCardsError {}
+
+impl fmt::Display for Cards {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bits = self.bits;
+        let mut first = true;
+        for i in (0..4).rev() {
+            let mut suit = (bits >> (16 * i)) & 0xffff;
+            if suit != 0 {
+                if !first {
+                    f.write_char(' ')?;
+                }
+                while suit != 0 {
+                    let next = 63 - suit.leading_zeros();
+                    f.write_char(RANKS[next as usize])?;
+                    suit -= 1 << next;
+                }
+                f.write_char(SUITS[i as usize])?;
+                first = false;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An iterator over a set of cards, ascending from the front and descending
+/// from the back.
+#[derive(Clone, Debug)]
+pub struct CardsIter {
+    bits: u64,
+}
+
+impl Iterator for CardsIter {
+    type Item = Card;
+
+    fn next(&mut self) -> Option<Card> {
+        if self.bits == 0 {
+            return None;
+        }
+        let next = self.bits.trailing_zeros();
+        self.bits &= self.bits - 1;
+        Some(Card(next as u8))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.bits.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for CardsIter {
+    fn next_back(&mut self) -> Option<Card> {
+        if self.bits == 0 {
+            return None;
+        }
+        let next = 63 - self.bits.leading_zeros();
+        self.bits -= 1 << next;
+        Some(Card(next as u8))
+    }
+}
+
+impl ExactSizeIterator for CardsIter {}
+
+impl FusedIterator for CardsIter {}
+
+impl IntoIterator for Cards {
+    type Item = Card;
+    type IntoIter = CardsIter;
+
+    fn into_iter(self) -> CardsIter {
+        self.iter()
+    }
+}
+
+impl FromIterator<Card> for Cards {
+    fn from_iter<I: IntoIterator<Item = Card>>(iter: I) -> Self {
+        let mut cards = Cards::empty();
+        cards.extend(iter);
+        cards
+    }
+}
+
+impl Extend<Card> for Cards {
+    fn extend<I: IntoIterator<Item = Card>>(&mut self, iter: I) {
+        for card in iter {
+            *self |= card;
+        }
+    }
+}
+
+impl BitOr<Card> for Cards {
+    type Output = Self;
+
+    fn bitor(self, rhs: Card) -> Self {
+        self | rhs.as_cards()
+    }
+}
+
+impl BitOrAssign<Card> for Cards {
+    fn bitor_assign(&mut self, rhs: Card) {
+        self.bitor_assign(rhs.as_cards());
+    }
+}
+
+impl Sub<Card> for Cards {
+    type Output = Self;
+
+    fn sub(self, rhs: Card) -> Self {
+        self - rhs.as_cards()
+    }
+}
+
+impl SubAssign<Card> for Cards {
+    fn sub_assign(&mut self, rhs: Card) {
+        self.sub_assign(rhs.as_cards());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cards_display() {
+        assert_eq!(
+            format!("{}", Cards::CHARGEABLE | Cards::NINES),
+            "Q9S A9H J9D T9C"
+        );
+    }
+
+    #[test]
+    fn test_cards_parse() {
+        assert_eq!(
+            Cards::parse("Q9S A9H J9D T9C").unwrap(),
+            Cards::CHARGEABLE | Cards::NINES
+        );
+    }
+
+    #[test]
+    fn test_cards_parse_lenient() {
+        assert_eq!(
+            Cards::parse("aqt5s 10♥  J3d").unwrap(),
+            Cards::parse("AQT5S TH J3D").unwrap()
+        );
+        assert_eq!(
+            "A♠ K♥ Q♦ J♣".parse::<Cards>().unwrap(),
+            Cards::parse("AS KH QD JC").unwrap()
+        );
+        assert_eq!(Cards::parse("").unwrap(), Cards::empty());
+        assert_eq!(Cards::parse("  ").unwrap(), Cards::empty());
+    }
+
+    #[test]
+    fn test_cards_parse_errors() {
+        let err = Cards::parse("AJXS").unwrap_err();
+        assert_eq!(err.kind(), ParseCardsErrorKind::InvalidRank);
+        assert_eq!(err.token(), "X");
+        assert_eq!(err.offset(), 2);
+        assert_eq!(err.to_string(), "invalid rank \"X\" at offset 2");
+
+        let err = Cards::parse("AJ5S KQX").unwrap_err();
+        assert_eq!(err.kind(), ParseCardsErrorKind::InvalidSuit);
+        assert_eq!((err.token(), err.offset()), ("X", 7));
+
+        let err = Cards::parse("♠ AH").unwrap_err();
+        assert_eq!(err.kind(), ParseCardsErrorKind::MissingRank);
+        assert_eq!((err.token(), err.offset()), ("♠", 0));
+
+        let err = Cards::parse("AS 1H").unwrap_err();
+        assert_eq!(err.kind(), ParseCardsErrorKind::InvalidRank);
+        assert_eq!((err.token(), err.offset()), ("1", 3));
+    }
+
+    #[test]
+    fn test_cards_iter() {
+        let cards = Cards::parse("QS A9H JD T9C").unwrap();
+        let names: Vec<String> = cards.iter().map(|c| c.to_string()).collect();
+        assert_eq!(names, ["9C", "TC", "JD", "9H", "AH", "QS"]);
+        let names: Vec<String> = cards.iter().rev().map(|c| c.to_string()).collect();
+        assert_eq!(names, ["QS", "AH", "9H", "JD", "TC", "9C"]);
+
+        let mut iter = cards.into_iter();
+        assert_eq!(iter.len(), 6);
+        assert_eq!(iter.next(), Some(Cards::parse("9C").unwrap().max()));
+        assert_eq!(iter.next_back(), Some(Cards::QUEEN_SPADES.max()));
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.by_ref().count(), 4);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        assert_eq!(Cards::empty().iter().len(), 0);
+        assert_eq!(Cards::all().iter().len(), 52);
+        assert_eq!(Cards::all().iter().next_back(), Some(Cards::all().max()));
+    }
+
+    #[test]
+    fn test_cards_collect() {
+        let cards = Cards::CHARGEABLE | Cards::NINES;
+        assert_eq!(cards.iter().collect::<Cards>(), cards);
+        assert_eq!(cards.iter().rev().collect::<Cards>(), cards);
+        assert_eq!(
+            Cards::HEARTS
+                .iter()
+                .filter(|&c| cards.contains(c.as_cards()))
+                .collect::<Cards>(),
+            Cards::parse("A9H").unwrap()
+        );
+
+        let mut extended = Cards::SPADES;
+        extended.extend(Cards::HEARTS);
+        assert_eq!(extended, Cards::SPADES | Cards::HEARTS);
+        assert_eq!(
+            Vec::<Card>::new().into_iter().collect::<Cards>(),
+            Cards::empty()
+        );
+    }
+
+    #[test]
+    fn test_cards_min_max() {
+        let cards = Cards::parse("K3S 5H T9D").unwrap();
+        assert_eq!(cards.min(), "9D".parse().unwrap());
+        assert_eq!(cards.max(), "KS".parse().unwrap());
+        assert_eq!(cards.in_suit(Suit::Hearts).min(), "5H".parse().unwrap());
+        assert_eq!(Cards::all().min(), Card::TWO_CLUBS);
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg(debug_assertions)]
+    fn test_cards_min_empty() {
+        Cards::empty().min();
+    }
+
+    #[test]
+    fn test_cards_suits() {
+        let cards = Cards::parse("AJT5S J63H 96D").unwrap();
+        assert_eq!(cards.in_suit(Suit::Spades), Cards::parse("AJT5S").unwrap());
+        assert_eq!(cards.in_suit(Suit::Clubs), Cards::empty());
+        assert_eq!(cards.suit_lengths(), [0, 2, 3, 4]);
+        assert_eq!(Cards::all().suit_lengths(), [13, 13, 13, 13]);
+        assert_eq!(Cards::empty().suit_lengths(), [0, 0, 0, 0]);
+        assert_eq!(cards.void_suits(), Cards::CLUBS);
+        assert_eq!(Cards::empty().void_suits(), Cards::all());
+        assert_eq!(Cards::all().void_suits(), Cards::empty());
+        assert_eq!(
+            Cards::parse("AS 2C").unwrap().void_suits(),
+            Cards::HEARTS | Cards::DIAMONDS
+        );
+    }
+
+    #[test]
+    fn test_cards_above_below() {
+        let cards = Cards::parse("AJT5S J63H 96D A953C").unwrap();
+        let ten: Card = "TS".parse().unwrap();
+        assert_eq!(cards.above(ten), Cards::parse("AJS").unwrap());
+        assert_eq!(cards.below(ten), Cards::parse("5S").unwrap());
+        let seven: Card = "7H".parse().unwrap();
+        assert_eq!(cards.above(seven), Cards::parse("JH").unwrap());
+        assert_eq!(cards.below(seven), Cards::parse("63H").unwrap());
+        assert_eq!(cards.above(Cards::SPADES.max()), Cards::empty());
+        assert_eq!(cards.below(Card::TWO_CLUBS), Cards::empty());
+        assert_eq!(
+            Cards::all().above(Card::TWO_CLUBS),
+            Cards::CLUBS - Card::TWO_CLUBS
+        );
+        assert_eq!(Cards::all().below(Cards::SPADES.max()).len(), 12);
+    }
+}
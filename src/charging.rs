@@ -0,0 +1,82 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use cards::Cards;
+use table::Seat;
+
+/// When the charging round happens relative to the pass. On keeper hands
+/// there is no pass, so the two are the same.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ChargeTiming {
+    BeforePass,
+    AfterPass,
+}
+
+/// Tracks whose turn it is to charge. Starting from the first seat, each
+/// player in turn charges some of their chargeable cards or passes, and the
+/// round continues around the table until every player passes in a row.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct ChargingRound {
+    next: Seat,
+    passes_in_a_row: usize,
+}
+
+impl ChargingRound {
+    pub fn new(first: Seat) -> ChargingRound {
+        ChargingRound {
+            next: first,
+            passes_in_a_row: 0,
+        }
+    }
+
+    pub fn next_to_act(&self) -> Seat {
+        self.next
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.passes_in_a_row == self.next.players()
+    }
+
+    /// Records the seat to act charging `cards`, where no cards is a pass.
+    pub fn record(&mut self, cards: Cards) {
+        if cards.is_empty() {
+            self.passes_in_a_row += 1;
+        } else {
+            self.passes_in_a_row = 0;
+        }
+        self.next = self.next.left();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_charging_round() {
+        let mut round = ChargingRound::new(Seat::new(2, 4));
+        assert_eq!(round.next_to_act(), Seat::new(2, 4));
+        round.record(Cards::empty());
+        round.record(Cards::empty());
+        round.record(Cards::TEN_CLUBS);
+        assert_eq!(round.next_to_act(), Seat::new(1, 4));
+        for _ in 0..3 {
+            round.record(Cards::empty());
+            assert!(!round.is_complete());
+        }
+        assert_eq!(round.next_to_act(), Seat::new(0, 4));
+        round.record(Cards::empty());
+        assert!(round.is_complete());
+    }
+
+    #[test]
+    fn test_charging_round_all_pass() {
+        let mut round = ChargingRound::new(Seat::new(0, 4));
+        for _ in 0..4 {
+            assert!(!round.is_complete());
+            round.record(Cards::empty());
+        }
+        assert!(round.is_complete());
+    }
+}
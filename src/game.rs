@@ -0,0 +1,1802 @@
+use std::error::Error;
+use std::fmt;
+use std::mem;
+
+use card::Card;
+use cards::Cards;
+use charging::{ChargeTiming, ChargingRound};
+use history::{HandRecord, Trick};
+use passing::PassDirection;
+use rules::{holder_of, is_nined, legal_plays, trick_winner, RuleSet};
+use scoring::score;
+use solver::{claim_holds, claim_play};
+use state::{deck, FullState};
+use table::{PerSeat, Seat};
+
+/// The stages a hand moves through, in order.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Phase {
+    /// Waiting for the cards to be dealt
+    Dealing,
+    /// Players are exchanging cards
+    Passing,
+    /// Players are charging cards
+    Charging,
+    /// Players are playing tricks
+    Playing,
+    /// Every card has been played and the hand is scored
+    Complete,
+}
+
+/// Something a player (or the dealer) does to move the game forward.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Action {
+    /// Deal these hands, one per seat
+    Deal(PerSeat<Cards>),
+    /// Choose the three cards to pass
+    Pass { seat: Seat, cards: Cards },
+    /// Charge the given cards, or none to decline
+    Charge { seat: Seat, cards: Cards },
+    /// Play a card to the current trick
+    Play { seat: Seat, card: Card },
+    /// Claim the outcome of the rest of the hand, which is then played out
+    /// if the claim holds however the other seats defend
+    Claim { seat: Seat, claim: Claim },
+}
+
+impl Action {
+    /// The seat taking the action, or `None` for the deal.
+    pub fn seat(self) -> Option<Seat> {
+        match self {
+            Action::Deal(_) => None,
+            Action::Pass { seat, .. }
+            | Action::Charge { seat, .. }
+            | Action::Play { seat, .. }
+            | Action::Claim { seat, .. } => Some(seat),
+        }
+    }
+}
+
+/// An outcome a player can claim for the cards still to be played.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Claim {
+    /// The claimant wins every remaining trick
+    AllTricks,
+    /// The claimant wins none of the remaining hearts, nor the queen of
+    /// spades where it counts, nor the ten of clubs where it doubles the
+    /// claimant's score
+    NoMorePoints,
+}
+
+impl Claim {
+    /// Whether the cards won so far already break the claim, where
+    /// `remaining` is every card that was still to be played when it was
+    /// made.
+    pub fn is_broken_by(
+        self,
+        claimant: Seat,
+        remaining: Cards,
+        won: PerSeat<Cards>,
+        rules: &RuleSet,
+    ) -> bool {
+        match self {
+            Claim::AllTricks => won
+                .seats()
+                .any(|seat| seat != claimant && won[seat].intersects(remaining)),
+            Claim::NoMorePoints => {
+                let mut penalties = rules.points - Cards::JACK_DIAMONDS;
+                if rules.ten_of_clubs_doubles {
+                    penalties |= Cards::TEN_CLUBS;
+                }
+                won[claimant].intersects(remaining & penalties)
+            }
+        }
+    }
+}
+
+/// What happened as the result of an action.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum GameEvent {
+    Dealt {
+        hands: PerSeat<Cards>,
+    },
+    Passed {
+        seat: Seat,
+        to: Seat,
+        cards: Cards,
+    },
+    Received {
+        seat: Seat,
+        from: Seat,
+        cards: Cards,
+    },
+    Charged {
+        seat: Seat,
+        cards: Cards,
+    },
+    Claimed {
+        seat: Seat,
+        claim: Claim,
+    },
+    CardPlayed {
+        seat: Seat,
+        card: Card,
+    },
+    /// The nine of the led suit played by `seat` sends the trick around
+    /// again
+    TrickExtendedByNine {
+        seat: Seat,
+        card: Card,
+    },
+    TrickWon {
+        seat: Seat,
+        cards: Cards,
+    },
+    /// `seat` took the first hearts of the hand, so hearts may now be led
+    HeartsBroken {
+        seat: Seat,
+    },
+    HandScored {
+        scores: PerSeat<i32>,
+    },
+}
+
+/// Something that wants to hear about everything that happens in a game,
+/// such as a display, a logger or a statistics collector.
+pub trait GameObserver {
+    fn on_event(&mut self, event: &GameEvent);
+}
+
+impl<F: FnMut(&GameEvent)> GameObserver for F {
+    fn on_event(&mut self, event: &GameEvent) {
+        self(event)
+    }
+}
+
+/// Why an action was rejected. A rejected action leaves the game unchanged.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum RuleViolation {
+    /// The action does not belong in the current phase
+    WrongPhase { phase: Phase },
+    /// Another seat is due to act
+    NotYourTurn { seat: Seat, expected: Seat },
+    /// The seat is from a table with a different number of players
+    NotAtTable { seat: Seat },
+    /// The hands are not one equal hand per seat covering the deck
+    InvalidDeal,
+    /// Exactly three cards must be passed
+    WrongPassSize { count: u32 },
+    /// The seat has already chosen its pass
+    AlreadyPassed { seat: Seat },
+    /// The seat does not hold these cards
+    NotHeld { seat: Seat, cards: Cards },
+    /// These cards cannot be charged
+    NotChargeable { cards: Cards },
+    /// These cards have already been charged
+    AlreadyCharged { cards: Cards },
+    /// Charged cards may not be passed
+    CannotPassCharged { cards: Cards },
+    /// The card is held but may not be played now
+    IllegalPlay { card: Card, legal: Cards },
+    /// Some defence would break the claim
+    ClaimRejected { seat: Seat, claim: Claim },
+    /// There is too much of the hand left to check the claim
+    ClaimTooEarly { seat: Seat, claim: Claim },
+    /// The match is over, so no more hands are played
+    MatchOver,
+}
+
+impl fmt::Display for RuleViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RuleViolation::WrongPhase { phase } => write!(f, "not allowed while {:?}", phase),
+            RuleViolation::NotYourTurn { seat, expected } => {
+                write!(f, "seat {} acted, but seat {} is to act", seat, expected)
+            }
+            RuleViolation::NotAtTable { seat } => write!(
+                f,
+                "seat {} is from a table of {} players",
+                seat,
+                seat.players()
+            ),
+            RuleViolation::InvalidDeal => write!(f, "hands do not form a full deal"),
+            RuleViolation::WrongPassSize { count } => {
+                write!(f, "three cards must be passed, not {}", count)
+            }
+            RuleViolation::AlreadyPassed { seat } => write!(f, "seat {} has already passed", seat),
+            RuleViolation::NotHeld { seat, cards } => {
+                write!(f, "seat {} does not hold {}", seat, cards)
+            }
+            RuleViolation::NotChargeable { cards } => write!(f, "{} cannot be charged", cards),
+            RuleViolation::AlreadyCharged { cards } => {
+                write!(f, "{} has already been charged", cards)
+            }
+            RuleViolation::CannotPassCharged { cards } => {
+                write!(f, "{} has been charged and cannot be passed", cards)
+            }
+            RuleViolation::IllegalPlay { card, legal } => {
+                write!(f, "{} cannot be played; legal plays are {}", card, legal)
+            }
+            RuleViolation::ClaimRejected { seat, claim } => {
+                write!(f, "seat {} cannot guarantee {:?}", seat, claim)
+            }
+            RuleViolation::ClaimTooEarly { seat, claim } => write!(
+                f,
+                "too much of the hand is left to check seat {}'s claim of {:?}",
+                seat, claim
+            ),
+            RuleViolation::MatchOver => write!(f, "the match is over"),
+        }
+    }
+}
+
+impl Error for RuleViolation {}
+
+/// A single hand of turbo hearts, driven one action at a time.
+pub struct Game {
+    state: FullState,
+    phase: Phase,
+    next: Seat,
+    /// The seat that dealt; the seat to its left charges first
+    dealer: Seat,
+    pass_direction: PassDirection,
+    rules: RuleSet,
+    /// The hands as dealt, before the pass
+    dealt: PerSeat<Cards>,
+    /// The cards each seat has chosen to pass
+    passed: PerSeat<Cards>,
+    /// The cards each seat received in the pass, once it is applied
+    received: PerSeat<Cards>,
+    charging: ChargingRound,
+    /// The seats and cards played to the current trick, in order
+    current_trick: Vec<(Seat, Card)>,
+    /// The completed tricks, in order
+    tricks: Vec<Trick>,
+    /// Every action applied so far, which together determine the state
+    history: Vec<Action>,
+    /// Undone actions, most recently undone last
+    undone: Vec<Action>,
+    observers: Vec<Box<dyn GameObserver>>,
+}
+
+impl Game {
+    /// A hand dealt by `dealer`, which must sit at a table of
+    /// `rules.players`.
+    pub fn new(dealer: Seat, pass_direction: PassDirection, rules: RuleSet) -> Game {
+        assert_eq!(
+            dealer.players(),
+            rules.players,
+            "the dealer is at another table"
+        );
+        Game {
+            state: FullState {
+                hand: PerSeat::new(rules.players),
+                won: PerSeat::new(rules.players),
+                charged: Cards::empty(),
+                led_suits: Cards::empty(),
+                trick_lead: Cards::empty(),
+                trick: Cards::empty(),
+            },
+            phase: Phase::Dealing,
+            next: dealer,
+            dealer,
+            pass_direction,
+            rules,
+            dealt: PerSeat::new(rules.players),
+            passed: PerSeat::new(rules.players),
+            received: PerSeat::new(rules.players),
+            charging: ChargingRound::new(dealer.left()),
+            current_trick: Vec::new(),
+            tricks: Vec::new(),
+            history: Vec::new(),
+            undone: Vec::new(),
+            observers: Vec::new(),
+        }
+    }
+
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    pub fn state(&self) -> &FullState {
+        &self.state
+    }
+
+    pub fn dealer(&self) -> Seat {
+        self.dealer
+    }
+
+    pub fn pass_direction(&self) -> PassDirection {
+        self.pass_direction
+    }
+
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    /// The cards `seat` chose to pass, or empty if it has not chosen yet.
+    pub fn passed(&self, seat: Seat) -> Cards {
+        self.passed[seat]
+    }
+
+    /// The cards `seat` received in the pass, or empty until every seat has
+    /// passed.
+    pub fn received(&self, seat: Seat) -> Cards {
+        self.received[seat]
+    }
+
+    /// The completed tricks, in the order they were played.
+    pub fn tricks(&self) -> &[Trick] {
+        &self.tricks
+    }
+
+    /// The seats and cards played so far to the trick in progress.
+    pub fn current_trick(&self) -> &[(Seat, Card)] {
+        &self.current_trick
+    }
+
+    /// The record of the hand, once it is complete.
+    pub fn record(&self) -> Option<HandRecord> {
+        if self.phase != Phase::Complete {
+            return None;
+        }
+        Some(HandRecord {
+            dealt: self.dealt,
+            pass_direction: self.pass_direction,
+            passed: self.passed,
+            charged: self.state.charged,
+            tricks: self.tricks.clone(),
+            scores: self.scores(),
+        })
+    }
+
+    /// The seat whose action the game is waiting on, or `None` before the
+    /// deal and once the hand is complete. Passes are chosen simultaneously,
+    /// so while passing this is the first seat that has yet to choose.
+    pub fn next_to_act(&self) -> Option<Seat> {
+        match self.phase {
+            Phase::Dealing | Phase::Complete => None,
+            _ => Some(self.next),
+        }
+    }
+
+    /// The cards the seat to act may legally play, or empty outside of play.
+    pub fn legal_plays(&self) -> Cards {
+        if self.phase != Phase::Playing {
+            return Cards::empty();
+        }
+        let state = &self.state;
+        legal_plays(
+            state.hand[self.next],
+            state.charged,
+            state.led_suits,
+            self.lead(),
+            self.hearts_broken(),
+            &self.rules,
+        )
+    }
+
+    /// The actions that led to the current position, in order.
+    pub fn history(&self) -> &[Action] {
+        &self.history
+    }
+
+    /// Registers an observer to be told of the events from every action
+    /// applied or redone from now on. Undoing an action emits no events.
+    pub fn add_observer(&mut self, observer: Box<dyn GameObserver>) {
+        self.observers.push(observer);
+    }
+
+    pub fn apply(&mut self, action: Action) -> Result<Vec<GameEvent>, RuleViolation> {
+        let events = self.apply_action(action)?;
+        self.history.push(action);
+        self.undone.clear();
+        self.notify(&events);
+        Ok(events)
+    }
+
+    /// Takes back the last action, whether a card, a pass or a charge, and
+    /// returns it.
+    pub fn undo(&mut self) -> Option<Action> {
+        let action = *self.history.last()?;
+        self.rewind(self.history.len() - 1);
+        Some(action)
+    }
+
+    /// Applies the most recently undone action again and returns it.
+    pub fn redo(&mut self) -> Option<Action> {
+        let action = self.undone.pop()?;
+        let events = self.apply_action(action).unwrap();
+        self.history.push(action);
+        self.notify(&events);
+        Some(action)
+    }
+
+    /// Takes back the cards played to the trick in progress, or to the last
+    /// completed trick if none have been played yet. A claim plays out the
+    /// rest of the hand in one action, so it is taken back whole. Returns
+    /// the number of cards taken back.
+    pub fn undo_trick(&mut self) -> usize {
+        let played = self.cards_played();
+        let count = match self.history.last() {
+            Some(Action::Claim { .. }) => 1,
+            _ if !self.current_trick.is_empty() => self.current_trick.len(),
+            _ => match self.tricks.last() {
+                Some(trick) => trick.plays.len(),
+                None => 0,
+            },
+        };
+        let len = self.history.len() - count;
+        self.rewind(len);
+        played - self.cards_played()
+    }
+
+    /// Takes back every card played, and any claim, returning to the
+    /// position just before the opening lead. Returns the number of cards
+    /// taken back.
+    pub fn rewind_to_play(&mut self) -> usize {
+        let played = self.cards_played();
+        let count = self
+            .history
+            .iter()
+            .rev()
+            .take_while(|action| matches!(action, Action::Play { .. } | Action::Claim { .. }))
+            .count();
+        let len = self.history.len() - count;
+        self.rewind(len);
+        played - self.cards_played()
+    }
+
+    /// The number of cards played so far, including to the trick in
+    /// progress.
+    fn cards_played(&self) -> usize {
+        let tricks: usize = self.tricks.iter().map(|trick| trick.plays.len()).sum();
+        tricks + self.current_trick.len()
+    }
+
+    /// Rebuilds the game from the first `len` actions of its history, saving
+    /// the rest so they can be redone.
+    fn rewind(&mut self, len: usize) {
+        let mut game = Game::new(self.dealer, self.pass_direction, self.rules);
+        for &action in &self.history[..len] {
+            game.apply_action(action).unwrap();
+        }
+        let undone = self.history.split_off(len);
+        game.history = mem::take(&mut self.history);
+        game.undone = mem::take(&mut self.undone);
+        game.undone.extend(undone.into_iter().rev());
+        game.observers = mem::take(&mut self.observers);
+        *self = game;
+    }
+
+    fn apply_action(&mut self, action: Action) -> Result<Vec<GameEvent>, RuleViolation> {
+        if let Some(seat) = action.seat() {
+            if seat.players() != self.rules.players {
+                return Err(RuleViolation::NotAtTable { seat });
+            }
+        }
+        match (self.phase, action) {
+            (Phase::Dealing, Action::Deal(hands)) => self.deal(hands),
+            (Phase::Passing, Action::Pass { seat, cards }) => self.pass(seat, cards),
+            (Phase::Charging, Action::Charge { seat, cards }) => self.charge(seat, cards),
+            (Phase::Playing, Action::Play { seat, card }) => self.play(seat, card),
+            (Phase::Playing, Action::Claim { seat, claim }) => self.claim(seat, claim),
+            (phase, _) => Err(RuleViolation::WrongPhase { phase }),
+        }
+    }
+
+    fn deal(&mut self, hands: PerSeat<Cards>) -> Result<Vec<GameEvent>, RuleViolation> {
+        let players = self.rules.players;
+        let deck = deck(players);
+        let size = deck.len() / players as u32;
+        let dealt = hands
+            .iter()
+            .fold(Cards::empty(), |dealt, &hand| dealt | hand);
+        if hands.players() != players
+            || dealt != deck
+            || hands.iter().any(|hand| hand.len() != size)
+        {
+            return Err(RuleViolation::InvalidDeal);
+        }
+        self.state.hand = hands;
+        self.dealt = hands;
+        self.advance();
+        Ok(vec![GameEvent::Dealt { hands }])
+    }
+
+    fn pass(&mut self, seat: Seat, cards: Cards) -> Result<Vec<GameEvent>, RuleViolation> {
+        if !self.passed[seat].is_empty() {
+            return Err(RuleViolation::AlreadyPassed { seat });
+        }
+        if cards.len() != 3 {
+            return Err(RuleViolation::WrongPassSize { count: cards.len() });
+        }
+        if !self.state.hand[seat].contains(cards) {
+            return Err(RuleViolation::NotHeld {
+                seat,
+                cards: cards - self.state.hand[seat],
+            });
+        }
+        if self.state.charged.intersects(cards) {
+            return Err(RuleViolation::CannotPassCharged {
+                cards: cards & self.state.charged,
+            });
+        }
+
+        self.passed[seat] = cards;
+        match self
+            .passed
+            .seats()
+            .find(|&seat| self.passed[seat].is_empty())
+        {
+            Some(next) => {
+                self.next = next;
+                Ok(Vec::new())
+            }
+            None => Ok(self.apply_passes()),
+        }
+    }
+
+    /// Exchanges every seat's chosen cards at once.
+    fn apply_passes(&mut self) -> Vec<GameEvent> {
+        let direction = self.pass_direction;
+        let mut events = Vec::new();
+        for seat in self.passed.seats() {
+            let to = direction.target(seat);
+            let cards = self.passed[seat];
+            self.state.hand[seat] -= cards;
+            self.received[to] = cards;
+            events.push(GameEvent::Passed { seat, to, cards });
+        }
+        for seat in self.received.seats() {
+            let from = direction.source(seat);
+            let cards = self.received[seat];
+            self.state.hand[seat] |= cards;
+            events.push(GameEvent::Received { seat, from, cards });
+        }
+        self.advance();
+        events
+    }
+
+    fn charge(&mut self, seat: Seat, cards: Cards) -> Result<Vec<GameEvent>, RuleViolation> {
+        self.check_turn(seat)?;
+        if !self.rules.chargeable().contains(cards) {
+            return Err(RuleViolation::NotChargeable {
+                cards: cards - self.rules.chargeable(),
+            });
+        }
+        if !self.state.hand[seat].contains(cards) {
+            return Err(RuleViolation::NotHeld {
+                seat,
+                cards: cards - self.state.hand[seat],
+            });
+        }
+        if self.state.charged.intersects(cards) {
+            return Err(RuleViolation::AlreadyCharged {
+                cards: cards & self.state.charged,
+            });
+        }
+
+        let mut events = Vec::new();
+        if !cards.is_empty() {
+            self.state.charged |= cards;
+            events.push(GameEvent::Charged { seat, cards });
+        }
+        self.charging.record(cards);
+        if self.charging.is_complete() {
+            self.advance();
+        } else {
+            self.next = self.charging.next_to_act();
+        }
+        Ok(events)
+    }
+
+    /// Moves on from the current phase to the next one, which depends on
+    /// whether there is a pass, whether there is charging and whether
+    /// charging comes before the pass.
+    fn advance(&mut self) {
+        let passing = self.pass_direction != PassDirection::Keeper;
+        let charging = self.rules.charging;
+        let charge_first = charging && self.rules.charge_timing == ChargeTiming::BeforePass;
+        self.phase = match self.phase {
+            Phase::Dealing if passing && !charge_first => Phase::Passing,
+            Phase::Dealing if charging => Phase::Charging,
+            Phase::Dealing => Phase::Playing,
+            Phase::Passing if charge_first || !charging => Phase::Playing,
+            Phase::Passing => Phase::Charging,
+            Phase::Charging if passing && charge_first => Phase::Passing,
+            Phase::Charging => Phase::Playing,
+            phase => phase,
+        };
+        self.next = match self.phase {
+            Phase::Charging => {
+                self.charging = ChargingRound::new(self.dealer.left());
+                self.charging.next_to_act()
+            }
+            Phase::Playing => {
+                holder_of(self.state.hand, Card::TWO_CLUBS).expect("the two of clubs was dealt")
+            }
+            _ => Seat::new(0, self.rules.players),
+        };
+    }
+
+    fn play(&mut self, seat: Seat, card: Card) -> Result<Vec<GameEvent>, RuleViolation> {
+        self.check_turn(seat)?;
+        if !self.state.hand[seat].contains(card.as_cards()) {
+            return Err(RuleViolation::NotHeld {
+                seat,
+                cards: card.as_cards(),
+            });
+        }
+        let legal = self.legal_plays();
+        if !legal.contains(card.as_cards()) {
+            return Err(RuleViolation::IllegalPlay { card, legal });
+        }
+
+        let hearts_broken = self.hearts_broken();
+        let mut events = vec![GameEvent::CardPlayed { seat, card }];
+        let state = &mut self.state;
+        if state.trick.is_empty() {
+            state.trick_lead = card.as_cards();
+        }
+        state.hand[seat] -= card;
+        state.trick |= card;
+        self.current_trick.push((seat, card));
+
+        let lead = state.trick_lead.max();
+        let players = self.rules.players;
+        let size = state.trick.len() as usize;
+        let finishes_trick = if size == 2 * players {
+            true
+        } else if size == players {
+            // a nine cannot extend the last trick, as no cards are left
+            !self.rules.nines_extend_tricks
+                || state.hand[seat].is_empty()
+                || !is_nined(state.trick, lead)
+        } else {
+            false
+        };
+        if !finishes_trick {
+            if size == players {
+                let nines = Cards::NINES & lead.suit();
+                let &(nined_by, nine) = self
+                    .current_trick
+                    .iter()
+                    .find(|&&(_, card)| nines.contains(card.as_cards()))
+                    .unwrap();
+                events.push(GameEvent::TrickExtendedByNine {
+                    seat: nined_by,
+                    card: nine,
+                });
+            }
+            self.next = seat.left();
+            return Ok(events);
+        }
+
+        let winning_card = trick_winner(state.trick, lead);
+        let winner = self
+            .current_trick
+            .iter()
+            .find(|&&(_, card)| card == winning_card)
+            .map(|&(seat, _)| seat)
+            .unwrap();
+        let cards = state.trick;
+        state.won[winner] |= cards;
+        state.led_suits |= lead.suit();
+        state.trick_lead = Cards::empty();
+        state.trick = Cards::empty();
+        let plays = self.current_trick.split_off(0);
+        self.tricks.push(Trick {
+            leader: plays[0].0,
+            nined: plays.len() > players,
+            plays,
+            winner,
+        });
+        self.next = winner;
+        events.push(GameEvent::TrickWon {
+            seat: winner,
+            cards,
+        });
+        if !hearts_broken && cards.intersects(Cards::HEARTS) {
+            events.push(GameEvent::HeartsBroken { seat: winner });
+        }
+
+        if state.hand.iter().all(|hand| hand.is_empty()) {
+            self.phase = Phase::Complete;
+            events.push(GameEvent::HandScored {
+                scores: self.scores(),
+            });
+        }
+        Ok(events)
+    }
+
+    /// Checks the claim against every defence and, if it holds, plays out
+    /// the rest of the hand: the claimant keeps to a line that makes the
+    /// claim and everyone else plays their lowest legal card.
+    fn claim(&mut self, seat: Seat, claim: Claim) -> Result<Vec<GameEvent>, RuleViolation> {
+        let (hand, lead) = self.solver_position();
+        let state = &self.state;
+        match claim_holds(
+            claim,
+            seat,
+            self.next,
+            hand,
+            state.won,
+            state.charged,
+            state.led_suits,
+            lead,
+            state.trick,
+            &self.rules,
+        ) {
+            Some(true) => {}
+            Some(false) => return Err(RuleViolation::ClaimRejected { seat, claim }),
+            None => return Err(RuleViolation::ClaimTooEarly { seat, claim }),
+        }
+
+        // put the game back as it was should the play out fail after all
+        let saved = (
+            self.state,
+            self.phase,
+            self.next,
+            self.current_trick.clone(),
+            self.tricks.len(),
+        );
+        let result = self.play_out_claim(seat, claim);
+        if result.is_err() {
+            let (state, phase, next, current_trick, tricks) = saved;
+            self.state = state;
+            self.phase = phase;
+            self.next = next;
+            self.current_trick = current_trick;
+            self.tricks.truncate(tricks);
+        }
+        result
+    }
+
+    fn play_out_claim(
+        &mut self,
+        seat: Seat,
+        claim: Claim,
+    ) -> Result<Vec<GameEvent>, RuleViolation> {
+        let mut events = vec![GameEvent::Claimed { seat, claim }];
+        while self.phase == Phase::Playing {
+            let card = if self.next == seat {
+                let (hand, lead) = self.solver_position();
+                let state = &self.state;
+                claim_play(
+                    claim,
+                    seat,
+                    hand,
+                    state.won,
+                    state.charged,
+                    state.led_suits,
+                    lead,
+                    state.trick,
+                    &self.rules,
+                )
+                .ok_or(RuleViolation::ClaimRejected { seat, claim })?
+            } else {
+                self.legal_plays().min()
+            };
+            events.extend(self.play(self.next, card)?);
+        }
+        Ok(events)
+    }
+
+    /// The hands and lead as the solver takes them, with the cards played
+    /// to the trick in progress still in their players' hands.
+    fn solver_position(&self) -> (PerSeat<Cards>, Option<Card>) {
+        let mut hand = self.state.hand;
+        for &(seat, card) in &self.current_trick {
+            hand[seat] |= card;
+        }
+        (hand, self.lead())
+    }
+
+    fn scores(&self) -> PerSeat<i32> {
+        PerSeat::from_fn(self.rules.players, |seat| {
+            score(self.state.won[seat], self.state.charged, &self.rules).total
+        })
+    }
+
+    fn notify(&mut self, events: &[GameEvent]) {
+        for observer in &mut self.observers {
+            for event in events {
+                observer.on_event(event);
+            }
+        }
+    }
+
+    fn check_turn(&self, seat: Seat) -> Result<(), RuleViolation> {
+        if seat != self.next {
+            return Err(RuleViolation::NotYourTurn {
+                seat,
+                expected: self.next,
+            });
+        }
+        Ok(())
+    }
+
+    fn lead(&self) -> Option<Card> {
+        if self.state.trick_lead.is_empty() {
+            None
+        } else {
+            Some(self.state.trick_lead.max())
+        }
+    }
+
+    fn hearts_broken(&self) -> bool {
+        self.state
+            .won
+            .iter()
+            .any(|won| won.intersects(Cards::HEARTS))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn hands() -> PerSeat<Cards> {
+        PerSeat::from([
+            Cards::parse("AJT5S J63H 96D A953C").unwrap(),
+            Cards::parse("9732S T92H K7D KT74C").unwrap(),
+            Cards::parse("KQ6S A5H JT542D Q82C").unwrap(),
+            Cards::parse("84S KQ874H AQ83D J6C").unwrap(),
+        ])
+    }
+
+    fn seat(index: usize) -> Seat {
+        Seat::new(index, 4)
+    }
+
+    fn card(s: &str) -> Card {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_deal() {
+        let mut game = Game::new(seat(3), PassDirection::Keeper, RuleSet::default());
+        assert_eq!(game.phase(), Phase::Dealing);
+        assert_eq!(game.next_to_act(), None);
+        let mut bad = hands();
+        bad[seat(0)] -= card("AS");
+        assert_eq!(
+            game.apply(Action::Deal(bad)),
+            Err(RuleViolation::InvalidDeal)
+        );
+        assert_eq!(
+            game.apply(Action::Deal(hands())),
+            Ok(vec![GameEvent::Dealt { hands: hands() }])
+        );
+        assert_eq!(game.phase(), Phase::Charging);
+        assert_eq!(game.next_to_act(), Some(seat(0)));
+        assert_eq!(
+            game.apply(Action::Deal(hands())),
+            Err(RuleViolation::WrongPhase {
+                phase: Phase::Charging
+            })
+        );
+    }
+
+    #[test]
+    fn test_pass() {
+        let mut game = Game::new(seat(3), PassDirection::Left, RuleSet::default());
+        game.apply(Action::Deal(hands())).unwrap();
+        assert_eq!(game.phase(), Phase::Passing);
+        assert_eq!(game.next_to_act(), Some(seat(0)));
+        let passes = PerSeat::from([
+            Cards::parse("AS 3H 9C").unwrap(),
+            Cards::parse("9S KD KC").unwrap(),
+            Cards::parse("QS AH JD").unwrap(),
+            Cards::parse("KQ8H").unwrap(),
+        ]);
+        assert_eq!(
+            game.apply(Action::Pass {
+                seat: seat(2),
+                cards: Cards::parse("QS AH").unwrap()
+            }),
+            Err(RuleViolation::WrongPassSize { count: 2 })
+        );
+        assert_eq!(
+            game.apply(Action::Pass {
+                seat: seat(1),
+                cards: Cards::parse("AKS 2C").unwrap()
+            }),
+            Err(RuleViolation::NotHeld {
+                seat: seat(1),
+                cards: Cards::parse("AKS 2C").unwrap()
+            })
+        );
+        assert_eq!(
+            game.apply(Action::Pass {
+                seat: seat(1),
+                cards: passes[seat(1)]
+            }),
+            Ok(vec![])
+        );
+        assert_eq!(
+            game.apply(Action::Pass {
+                seat: seat(1),
+                cards: passes[seat(1)]
+            }),
+            Err(RuleViolation::AlreadyPassed { seat: seat(1) })
+        );
+        assert_eq!(game.passed(seat(1)), passes[seat(1)]);
+        assert_eq!(game.received(seat(2)), Cards::empty());
+        assert_eq!(game.state().hand, hands());
+        assert_eq!(game.next_to_act(), Some(seat(0)));
+
+        game.apply(Action::Pass {
+            seat: seat(0),
+            cards: passes[seat(0)],
+        })
+        .unwrap();
+        assert_eq!(game.next_to_act(), Some(seat(2)));
+        game.apply(Action::Pass {
+            seat: seat(3),
+            cards: passes[seat(3)],
+        })
+        .unwrap();
+        let events = game
+            .apply(Action::Pass {
+                seat: seat(2),
+                cards: passes[seat(2)],
+            })
+            .unwrap();
+        assert_eq!(events.len(), 8);
+        assert_eq!(
+            events[0],
+            GameEvent::Passed {
+                seat: seat(0),
+                to: seat(1),
+                cards: passes[seat(0)]
+            }
+        );
+        assert_eq!(
+            events[4],
+            GameEvent::Received {
+                seat: seat(0),
+                from: seat(3),
+                cards: passes[seat(3)]
+            }
+        );
+        for seat in Seat::all(4) {
+            let from = seat.right();
+            assert_eq!(game.received(seat), passes[from]);
+            assert_eq!(
+                game.state().hand[seat],
+                (hands()[seat] - passes[seat]) | passes[from]
+            );
+        }
+        assert_eq!(game.phase(), Phase::Charging);
+        assert_eq!(game.next_to_act(), Some(seat(0)));
+    }
+
+    #[test]
+    fn test_not_at_table() {
+        let mut game = Game::new(seat(3), PassDirection::Left, RuleSet::default());
+        game.apply(Action::Deal(hands())).unwrap();
+        let stranger = Seat::new(4, 5);
+        let actions = [
+            Action::Pass {
+                seat: stranger,
+                cards: Cards::parse("AS 3H 9C").unwrap(),
+            },
+            Action::Charge {
+                seat: stranger,
+                cards: Cards::empty(),
+            },
+            Action::Play {
+                seat: stranger,
+                card: Card::TWO_CLUBS,
+            },
+            Action::Claim {
+                seat: stranger,
+                claim: Claim::AllTricks,
+            },
+        ];
+        for &action in &actions {
+            assert_eq!(
+                game.apply(action),
+                Err(RuleViolation::NotAtTable { seat: stranger })
+            );
+        }
+        assert_eq!(game.history().len(), 1);
+    }
+
+    #[test]
+    fn test_keeper() {
+        let mut game = Game::new(seat(3), PassDirection::Keeper, RuleSet::default());
+        game.apply(Action::Deal(hands())).unwrap();
+        assert_eq!(game.phase(), Phase::Charging);
+        assert_eq!(
+            game.apply(Action::Pass {
+                seat: seat(0),
+                cards: Cards::parse("AJTS").unwrap()
+            }),
+            Err(RuleViolation::WrongPhase {
+                phase: Phase::Charging
+            })
+        );
+    }
+
+    #[test]
+    fn test_charge() {
+        let mut game = Game::new(seat(3), PassDirection::Keeper, RuleSet::default());
+        game.apply(Action::Deal(hands())).unwrap();
+        assert_eq!(
+            game.apply(Action::Charge {
+                seat: seat(1),
+                cards: Cards::empty()
+            }),
+            Err(RuleViolation::NotYourTurn {
+                seat: seat(1),
+                expected: seat(0)
+            })
+        );
+        assert_eq!(
+            game.apply(Action::Charge {
+                seat: seat(0),
+                cards: Cards::QUEEN_SPADES
+            }),
+            Err(RuleViolation::NotHeld {
+                seat: seat(0),
+                cards: Cards::QUEEN_SPADES
+            })
+        );
+        assert_eq!(
+            game.apply(Action::Charge {
+                seat: seat(0),
+                cards: Cards::parse("AS").unwrap()
+            }),
+            Err(RuleViolation::NotChargeable {
+                cards: Cards::parse("AS").unwrap()
+            })
+        );
+        assert_eq!(
+            game.apply(Action::Charge {
+                seat: seat(0),
+                cards: Cards::empty()
+            }),
+            Ok(vec![])
+        );
+        assert_eq!(
+            game.apply(Action::Charge {
+                seat: seat(1),
+                cards: Cards::TEN_CLUBS
+            }),
+            Ok(vec![GameEvent::Charged {
+                seat: seat(1),
+                cards: Cards::TEN_CLUBS
+            }])
+        );
+        game.apply(Action::Charge {
+            seat: seat(2),
+            cards: Cards::QUEEN_SPADES | Cards::ACE_HEARTS,
+        })
+        .unwrap();
+        for index in 3..5 {
+            game.apply(Action::Charge {
+                seat: seat(index % 4),
+                cards: Cards::empty(),
+            })
+            .unwrap();
+        }
+        assert_eq!(
+            game.apply(Action::Charge {
+                seat: seat(1),
+                cards: Cards::TEN_CLUBS
+            }),
+            Err(RuleViolation::AlreadyCharged {
+                cards: Cards::TEN_CLUBS
+            })
+        );
+        game.apply(Action::Charge {
+            seat: seat(1),
+            cards: Cards::empty(),
+        })
+        .unwrap();
+        assert_eq!(game.phase(), Phase::Charging);
+        assert_eq!(game.next_to_act(), Some(seat(2)));
+        game.apply(Action::Charge {
+            seat: seat(2),
+            cards: Cards::empty(),
+        })
+        .unwrap();
+        assert_eq!(game.phase(), Phase::Playing);
+        assert_eq!(
+            game.state().charged,
+            Cards::TEN_CLUBS | Cards::QUEEN_SPADES | Cards::ACE_HEARTS
+        );
+        assert_eq!(game.next_to_act(), Some(seat(2)));
+        assert_eq!(game.legal_plays(), Cards::TWO_CLUBS);
+    }
+
+    #[test]
+    fn test_charge_before_pass() {
+        let mut game = Game::new(
+            seat(3),
+            PassDirection::Right,
+            RuleSet {
+                charge_timing: ChargeTiming::BeforePass,
+                ..RuleSet::default()
+            },
+        );
+        game.apply(Action::Deal(hands())).unwrap();
+        assert_eq!(game.phase(), Phase::Charging);
+        assert_eq!(
+            game.apply(Action::Charge {
+                seat: seat(0),
+                cards: Cards::empty()
+            }),
+            Ok(vec![])
+        );
+        assert_eq!(
+            game.apply(Action::Charge {
+                seat: seat(1),
+                cards: Cards::TEN_CLUBS
+            }),
+            Ok(vec![GameEvent::Charged {
+                seat: seat(1),
+                cards: Cards::TEN_CLUBS
+            }])
+        );
+        for index in 2..6 {
+            game.apply(Action::Charge {
+                seat: seat(index % 4),
+                cards: Cards::empty(),
+            })
+            .unwrap();
+        }
+        assert_eq!(game.phase(), Phase::Passing);
+        assert_eq!(
+            game.apply(Action::Pass {
+                seat: seat(1),
+                cards: Cards::parse("TC 9S 2H").unwrap()
+            }),
+            Err(RuleViolation::CannotPassCharged {
+                cards: Cards::TEN_CLUBS
+            })
+        );
+        for seat in Seat::all(4) {
+            let cards = (game.state().hand[seat] - Cards::TEN_CLUBS)
+                .iter()
+                .take(3)
+                .collect();
+            game.apply(Action::Pass { seat, cards }).unwrap();
+        }
+        assert_eq!(game.phase(), Phase::Playing);
+        assert_eq!(game.state().charged, Cards::TEN_CLUBS);
+    }
+
+    #[test]
+    fn test_play() {
+        let mut game = Game::new(seat(3), PassDirection::Keeper, RuleSet::default());
+        game.apply(Action::Deal(hands())).unwrap();
+        for seat in Seat::all(4) {
+            game.apply(Action::Charge {
+                seat,
+                cards: Cards::empty(),
+            })
+            .unwrap();
+        }
+        assert_eq!(
+            game.apply(Action::Play {
+                seat: seat(2),
+                card: card("QC")
+            }),
+            Err(RuleViolation::IllegalPlay {
+                card: card("QC"),
+                legal: Cards::TWO_CLUBS
+            })
+        );
+        game.apply(Action::Play {
+            seat: seat(2),
+            card: Card::TWO_CLUBS,
+        })
+        .unwrap();
+        assert_eq!(
+            game.apply(Action::Play {
+                seat: seat(3),
+                card: card("KH")
+            }),
+            Err(RuleViolation::IllegalPlay {
+                card: card("KH"),
+                legal: Cards::parse("J6C").unwrap()
+            })
+        );
+        game.apply(Action::Play {
+            seat: seat(3),
+            card: card("JC"),
+        })
+        .unwrap();
+        game.apply(Action::Play {
+            seat: seat(0),
+            card: card("AC"),
+        })
+        .unwrap();
+        assert_eq!(
+            game.apply(Action::Play {
+                seat: seat(1),
+                card: card("KC")
+            }),
+            Ok(vec![
+                GameEvent::CardPlayed {
+                    seat: seat(1),
+                    card: card("KC")
+                },
+                GameEvent::TrickWon {
+                    seat: seat(0),
+                    cards: Cards::parse("AKJ2C").unwrap()
+                },
+            ])
+        );
+        assert_eq!(game.next_to_act(), Some(seat(0)));
+        assert_eq!(game.state().led_suits, Cards::CLUBS);
+        assert_eq!(game.current_trick(), &[]);
+        assert_eq!(
+            game.tricks(),
+            &[Trick {
+                leader: seat(2),
+                plays: vec![
+                    (seat(2), Card::TWO_CLUBS),
+                    (seat(3), card("JC")),
+                    (seat(0), card("AC")),
+                    (seat(1), card("KC")),
+                ],
+                winner: seat(0),
+                nined: false,
+            }]
+        );
+        assert_eq!(game.record(), None);
+    }
+
+    #[test]
+    fn test_nined_trick() {
+        let mut game = Game::new(seat(3), PassDirection::Keeper, RuleSet::default());
+        game.apply(Action::Deal(hands())).unwrap();
+        for seat in Seat::all(4) {
+            game.apply(Action::Charge {
+                seat,
+                cards: Cards::empty(),
+            })
+            .unwrap();
+        }
+        let plays = [
+            (seat(2), Card::TWO_CLUBS),
+            (seat(3), card("JC")),
+            (seat(0), card("9C")),
+            (seat(1), card("KC")),
+            (seat(2), card("QC")),
+            (seat(3), card("6C")),
+            (seat(0), card("AC")),
+        ];
+        for (i, &(seat, card)) in plays.iter().enumerate() {
+            let events = game.apply(Action::Play { seat, card }).unwrap();
+            if i == 3 {
+                assert_eq!(
+                    events[1],
+                    GameEvent::TrickExtendedByNine {
+                        seat: plays[2].0,
+                        card: plays[2].1,
+                    }
+                );
+            } else {
+                assert_eq!(events, [GameEvent::CardPlayed { seat, card }]);
+            }
+        }
+        assert_eq!(game.current_trick(), &plays[..]);
+        assert!(game.tricks().is_empty());
+        game.apply(Action::Play {
+            seat: seat(1),
+            card: card("TC"),
+        })
+        .unwrap();
+        let trick = &game.tricks()[0];
+        assert_eq!(trick.leader, seat(2));
+        assert_eq!(trick.plays.len(), 8);
+        assert_eq!(trick.winner, seat(0));
+        assert!(trick.nined);
+        assert_eq!(trick.lead(), Card::TWO_CLUBS);
+        assert_eq!(trick.cards(), Cards::parse("AKQJT962C").unwrap());
+        assert_eq!(game.next_to_act(), Some(seat(0)));
+    }
+
+    #[test]
+    fn test_observer() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut game = Game::new(seat(3), PassDirection::Keeper, RuleSet::default());
+        let log = Rc::clone(&seen);
+        game.add_observer(Box::new(move |event: &GameEvent| {
+            log.borrow_mut().push(*event)
+        }));
+        let mut emitted = game.apply(Action::Deal(hands())).unwrap();
+        for seat in Seat::all(4) {
+            emitted.extend(
+                game.apply(Action::Charge {
+                    seat,
+                    cards: Cards::empty(),
+                })
+                .unwrap(),
+            );
+        }
+        let mut hearts_broken = 0;
+        while let Some(seat) = game.next_to_act() {
+            let card = game.legal_plays().max();
+            let events = game.apply(Action::Play { seat, card }).unwrap();
+            for event in &events {
+                if let GameEvent::HeartsBroken { seat } = *event {
+                    hearts_broken += 1;
+                    assert_eq!(
+                        events[events.len() - 2],
+                        GameEvent::TrickWon {
+                            seat,
+                            cards: game.tricks().last().unwrap().cards(),
+                        }
+                    );
+                }
+            }
+            emitted.extend(events);
+        }
+        assert_eq!(hearts_broken, 1);
+        assert_eq!(*seen.borrow(), emitted);
+
+        let nined: Vec<_> = game.tricks().iter().filter(|trick| trick.nined).collect();
+        let extended: Vec<_> = emitted
+            .iter()
+            .filter_map(|event| match *event {
+                GameEvent::TrickExtendedByNine { seat, card } => Some((seat, card)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(extended.len(), nined.len());
+        for (trick, &(seat, card)) in nined.iter().zip(&extended) {
+            assert!(trick.plays[..4].contains(&(seat, card)));
+            assert!(card
+                .as_cards()
+                .intersects(Cards::NINES & trick.lead().suit()));
+        }
+
+        seen.borrow_mut().clear();
+        game.undo().unwrap();
+        assert!(seen.borrow().is_empty());
+        game.redo().unwrap();
+        assert_eq!(seen.borrow().last(), emitted.last());
+        seen.borrow_mut().clear();
+        let plays = game.rewind_to_play();
+        assert!(plays > 0);
+        assert!(seen.borrow().is_empty());
+        game.redo().unwrap();
+        assert_eq!(seen.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_nines_do_not_extend() {
+        let rules = RuleSet {
+            nines_extend_tricks: false,
+            ..RuleSet::default()
+        };
+        let mut game = Game::new(seat(3), PassDirection::Keeper, rules);
+        game.apply(Action::Deal(hands())).unwrap();
+        for seat in Seat::all(4) {
+            game.apply(Action::Charge {
+                seat,
+                cards: Cards::empty(),
+            })
+            .unwrap();
+        }
+        for &(seat, card) in &[
+            (seat(2), Card::TWO_CLUBS),
+            (seat(3), card("JC")),
+            (seat(0), card("9C")),
+            (seat(1), card("KC")),
+        ] {
+            game.apply(Action::Play { seat, card }).unwrap();
+        }
+        let trick = &game.tricks()[0];
+        assert_eq!(trick.plays.len(), 4);
+        assert_eq!(trick.winner, seat(1));
+        assert!(!trick.nined);
+        assert_eq!(game.next_to_act(), Some(seat(1)));
+    }
+
+    #[test]
+    fn test_no_charging() {
+        let mut game = Game::new(seat(3), PassDirection::Left, RuleSet::classic());
+        game.apply(Action::Deal(hands())).unwrap();
+        assert_eq!(game.phase(), Phase::Passing);
+        for seat in Seat::all(4) {
+            let cards = game.state().hand[seat].iter().take(3).collect();
+            game.apply(Action::Pass { seat, cards }).unwrap();
+        }
+        assert_eq!(game.phase(), Phase::Playing);
+
+        let mut game = Game::new(seat(3), PassDirection::Keeper, RuleSet::omnibus());
+        game.apply(Action::Deal(hands())).unwrap();
+        assert_eq!(game.phase(), Phase::Playing);
+        assert_eq!(
+            game.apply(Action::Charge {
+                seat: seat(0),
+                cards: Cards::empty(),
+            }),
+            Err(RuleViolation::WrongPhase {
+                phase: Phase::Playing
+            })
+        );
+    }
+
+    #[test]
+    fn test_full_hand() {
+        let mut game = Game::new(seat(3), PassDirection::Keeper, RuleSet::default());
+        game.apply(Action::Deal(hands())).unwrap();
+        for seat in Seat::all(4) {
+            game.apply(Action::Charge {
+                seat,
+                cards: Cards::empty(),
+            })
+            .unwrap();
+        }
+        let mut last = Vec::new();
+        while let Some(seat) = game.next_to_act() {
+            let card = game.legal_plays().max();
+            last = game.apply(Action::Play { seat, card }).unwrap();
+        }
+        assert_eq!(game.phase(), Phase::Complete);
+        let won = game.state().won;
+        assert_eq!(
+            won[seat(0)] | won[seat(1)] | won[seat(2)] | won[seat(3)],
+            Cards::all()
+        );
+        let scores = PerSeat::from([
+            score(won[seat(0)], Cards::empty(), &RuleSet::default()).total,
+            score(won[seat(1)], Cards::empty(), &RuleSet::default()).total,
+            score(won[seat(2)], Cards::empty(), &RuleSet::default()).total,
+            score(won[seat(3)], Cards::empty(), &RuleSet::default()).total,
+        ]);
+        assert_eq!(last.last(), Some(&GameEvent::HandScored { scores }));
+
+        let record = game.record().unwrap();
+        assert_eq!(record.dealt, hands());
+        assert_eq!(record.tricks, game.tricks());
+        assert_eq!(record.won(), won);
+        assert_eq!(record.scores, scores);
+        for trick in &record.tricks {
+            assert_eq!(trick.nined, trick.plays.len() == 8);
+            assert_eq!(trick.leader, trick.plays[0].0);
+            assert!(won[trick.winner].contains(trick.cards()));
+        }
+        assert_eq!(
+            game.apply(Action::Play {
+                seat: seat(0),
+                card: Card::TWO_CLUBS
+            }),
+            Err(RuleViolation::WrongPhase {
+                phase: Phase::Complete
+            })
+        );
+    }
+
+    #[test]
+    fn test_claim() {
+        let mut game = Game::new(seat(3), PassDirection::Keeper, RuleSet::default());
+        game.apply(Action::Deal(hands())).unwrap();
+        for seat in Seat::all(4) {
+            game.apply(Action::Charge {
+                seat,
+                cards: Cards::empty(),
+            })
+            .unwrap();
+        }
+        while game.state().hand.iter().any(|hand| hand.len() > 1) {
+            let seat = game.next_to_act().unwrap();
+            let card = game.legal_plays().min();
+            game.apply(Action::Play { seat, card }).unwrap();
+        }
+        let before = snapshot(&game);
+        let mut accepted = Vec::new();
+        for seat in Seat::all(4) {
+            match game.apply(Action::Claim {
+                seat,
+                claim: Claim::AllTricks,
+            }) {
+                Ok(events) => {
+                    assert_eq!(
+                        events[0],
+                        GameEvent::Claimed {
+                            seat,
+                            claim: Claim::AllTricks
+                        }
+                    );
+                    assert_eq!(game.phase(), Phase::Complete);
+                    assert_eq!(game.tricks().last().unwrap().winner, seat);
+                    accepted.push(seat);
+                    game.undo();
+                }
+                Err(violation) => assert_eq!(
+                    violation,
+                    RuleViolation::ClaimRejected {
+                        seat,
+                        claim: Claim::AllTricks
+                    }
+                ),
+            }
+            assert_eq!(snapshot(&game), before);
+        }
+        assert_eq!(accepted.len(), 1);
+
+        let seat = game.next_to_act().unwrap();
+        let card = game.legal_plays().min();
+        game.apply(Action::Play { seat, card }).unwrap();
+        game.apply(Action::Claim {
+            seat: accepted[0],
+            claim: Claim::AllTricks,
+        })
+        .unwrap();
+        let after = snapshot(&game);
+        game.undo();
+        assert_eq!(game.current_trick().len(), 1);
+        game.redo();
+        assert_eq!(snapshot(&game), after);
+        assert!(game.record().is_some());
+    }
+
+    #[test]
+    fn test_claim_too_early() {
+        let mut game = Game::new(seat(3), PassDirection::Keeper, RuleSet::default());
+        game.apply(Action::Deal(hands())).unwrap();
+        for seat in Seat::all(4) {
+            game.apply(Action::Charge {
+                seat,
+                cards: Cards::empty(),
+            })
+            .unwrap();
+        }
+        let before = snapshot(&game);
+        let claim = Claim::NoMorePoints;
+        let too_early = Seat::all(4)
+            .filter(|&seat| {
+                let result = game.apply(Action::Claim { seat, claim });
+                assert_eq!(snapshot(&game), before);
+                result == Err(RuleViolation::ClaimTooEarly { seat, claim })
+            })
+            .count();
+        assert!(too_early > 0);
+    }
+
+    #[test]
+    fn test_claim_settles_rest() {
+        let mut game = Game::new(seat(3), PassDirection::Keeper, RuleSet::default());
+        game.apply(Action::Deal(hands())).unwrap();
+        assert_eq!(
+            game.apply(Action::Claim {
+                seat: seat(0),
+                claim: Claim::NoMorePoints
+            }),
+            Err(RuleViolation::WrongPhase {
+                phase: Phase::Charging
+            })
+        );
+        for seat in Seat::all(4) {
+            game.apply(Action::Charge {
+                seat,
+                cards: Cards::empty(),
+            })
+            .unwrap();
+        }
+        while game.tricks().len() < 9 {
+            let seat = game.next_to_act().unwrap();
+            let card = game.legal_plays().max();
+            game.apply(Action::Play { seat, card }).unwrap();
+        }
+        let remaining = game
+            .state()
+            .hand
+            .iter()
+            .fold(Cards::empty(), |all, &h| all | h);
+        let penalties = remaining & (Cards::HEARTS | Cards::QUEEN_SPADES);
+        let mut accepted = 0;
+        for seat in Seat::all(4) {
+            let won = game.state().won[seat];
+            if let Ok(events) = game.apply(Action::Claim {
+                seat,
+                claim: Claim::NoMorePoints,
+            }) {
+                assert_eq!(game.phase(), Phase::Complete);
+                assert_eq!(game.state().won[seat] & penalties, won & penalties);
+                assert_eq!(
+                    events
+                        .iter()
+                        .filter(|event| matches!(event, GameEvent::CardPlayed { .. }))
+                        .count(),
+                    remaining.len() as usize
+                );
+                game.undo();
+                accepted += 1;
+            }
+        }
+        assert!(accepted > 0);
+    }
+
+    /// The parts of the game that undo must restore.
+    fn snapshot(game: &Game) -> (FullState, Phase, Option<Seat>, Cards, Vec<Trick>, usize) {
+        (
+            *game.state(),
+            game.phase(),
+            game.next_to_act(),
+            game.legal_plays(),
+            game.tricks().to_vec(),
+            game.current_trick().len(),
+        )
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut game = Game::new(seat(3), PassDirection::Across, RuleSet::default());
+        let mut snapshots = vec![snapshot(&game)];
+        game.apply(Action::Deal(hands())).unwrap();
+        snapshots.push(snapshot(&game));
+        for seat in Seat::all(4) {
+            let cards = game.state().hand[seat].iter().rev().take(3).collect();
+            game.apply(Action::Pass { seat, cards }).unwrap();
+            snapshots.push(snapshot(&game));
+        }
+        while game.phase() == Phase::Charging {
+            let seat = game.next_to_act().unwrap();
+            let cards = (game.state().hand[seat] & Cards::CHARGEABLE) - game.state().charged;
+            game.apply(Action::Charge { seat, cards }).unwrap();
+            snapshots.push(snapshot(&game));
+        }
+        let hand = game.state().hand;
+        while let Some(seat) = game.next_to_act() {
+            let card = game.legal_plays().min();
+            game.apply(Action::Play { seat, card }).unwrap();
+            snapshots.push(snapshot(&game));
+        }
+        assert_eq!(game.phase(), Phase::Complete);
+        let history = game.history().to_vec();
+        assert_eq!(history.len() + 1, snapshots.len());
+
+        for i in (0..history.len()).rev() {
+            assert_eq!(game.undo(), Some(history[i]));
+            assert_eq!(snapshot(&game), snapshots[i]);
+        }
+        assert_eq!(game.undo(), None);
+        for i in 0..history.len() {
+            assert_eq!(game.redo(), Some(history[i]));
+            assert_eq!(snapshot(&game), snapshots[i + 1]);
+        }
+        assert_eq!(game.redo(), None);
+
+        let plays = game.rewind_to_play();
+        assert_eq!(game.phase(), Phase::Playing);
+        assert_eq!(game.state().hand, hand);
+        assert_eq!(game.state().led_suits, Cards::empty());
+        assert_eq!(game.state().trick, Cards::empty());
+        assert!(game.tricks().is_empty());
+        assert_eq!(snapshot(&game), snapshots[history.len() - plays]);
+        assert_eq!(game.rewind_to_play(), 0);
+        assert_eq!(game.redo(), Some(history[history.len() - plays]));
+    }
+
+    #[test]
+    fn test_undo_trick() {
+        let mut game = Game::new(seat(3), PassDirection::Keeper, RuleSet::default());
+        game.apply(Action::Deal(hands())).unwrap();
+        for seat in Seat::all(4) {
+            game.apply(Action::Charge {
+                seat,
+                cards: Cards::empty(),
+            })
+            .unwrap();
+        }
+        let start = snapshot(&game);
+        for _ in 0..6 {
+            let seat = game.next_to_act().unwrap();
+            let card = game.legal_plays().max();
+            game.apply(Action::Play { seat, card }).unwrap();
+        }
+        let after_trick = game.tricks()[0].clone();
+        assert_eq!(game.current_trick().len(), 2);
+        assert_eq!(game.undo_trick(), 2);
+        assert_eq!(game.current_trick().len(), 0);
+        assert_eq!(game.tricks().len(), 1);
+        assert_eq!(game.tricks()[0], after_trick);
+        assert_eq!(game.next_to_act(), Some(after_trick.winner));
+        assert_eq!(game.undo_trick(), 4);
+        assert_eq!(snapshot(&game), start);
+        assert_eq!(game.undo_trick(), 0);
+
+        game.redo().unwrap();
+        assert_eq!(game.current_trick().len(), 1);
+        let seat = game.next_to_act().unwrap();
+        let card = game.legal_plays().min();
+        game.apply(Action::Play { seat, card }).unwrap();
+        assert_eq!(game.redo(), None);
+    }
+
+    #[test]
+    fn test_undo_claim() {
+        let mut game = Game::new(seat(3), PassDirection::Keeper, RuleSet::default());
+        game.apply(Action::Deal(hands())).unwrap();
+        for seat in Seat::all(4) {
+            game.apply(Action::Charge {
+                seat,
+                cards: Cards::empty(),
+            })
+            .unwrap();
+        }
+        while game.state().hand.iter().any(|hand| hand.len() > 1) {
+            let seat = game.next_to_act().unwrap();
+            let card = game.legal_plays().min();
+            game.apply(Action::Play { seat, card }).unwrap();
+        }
+        let before = snapshot(&game);
+        let tricks = game.tricks().len();
+        let claim = Seat::all(4)
+            .map(|seat| Action::Claim {
+                seat,
+                claim: Claim::AllTricks,
+            })
+            .find(|&claim| game.apply(claim).is_ok())
+            .unwrap();
+        assert_eq!(game.phase(), Phase::Complete);
+
+        assert_eq!(game.undo_trick(), 4);
+        assert_eq!(snapshot(&game), before);
+        assert_eq!(game.tricks().len(), tricks);
+        assert_eq!(game.redo(), Some(claim));
+        assert_eq!(game.phase(), Phase::Complete);
+        assert_eq!(game.rewind_to_play(), 52);
+        assert!(game.tricks().is_empty());
+        assert!(game.current_trick().is_empty());
+        assert_eq!(game.phase(), Phase::Playing);
+    }
+
+    #[test]
+    fn test_undo_opening_claim() {
+        let mut game = Game::new(seat(3), PassDirection::Keeper, RuleSet::classic());
+        let hands = PerSeat::from([Cards::CLUBS, Cards::DIAMONDS, Cards::SPADES, Cards::HEARTS]);
+        game.apply(Action::Deal(hands)).unwrap();
+        let claim = Action::Claim {
+            seat: seat(0),
+            claim: Claim::AllTricks,
+        };
+        game.apply(claim).unwrap();
+        assert_eq!(game.state().won[seat(0)], Cards::all());
+
+        assert_eq!(game.undo_trick(), 52);
+        assert_eq!(game.history(), [Action::Deal(hands)]);
+        assert_eq!(game.phase(), Phase::Playing);
+        assert_eq!(game.undo_trick(), 0);
+        assert_eq!(game.redo(), Some(claim));
+        assert_eq!(game.rewind_to_play(), 52);
+        assert_eq!(game.history(), [Action::Deal(hands)]);
+    }
+
+    #[test]
+    fn test_undo_pass() {
+        let mut game = Game::new(seat(3), PassDirection::Left, RuleSet::default());
+        game.apply(Action::Deal(hands())).unwrap();
+        let pass = Cards::parse("AJTS").unwrap();
+        game.apply(Action::Pass {
+            seat: seat(0),
+            cards: pass,
+        })
+        .unwrap();
+        assert_eq!(game.passed(seat(0)), pass);
+        assert_eq!(
+            game.undo(),
+            Some(Action::Pass {
+                seat: seat(0),
+                cards: pass
+            })
+        );
+        assert_eq!(game.passed(seat(0)), Cards::empty());
+        assert_eq!(game.phase(), Phase::Passing);
+        assert_eq!(game.undo(), Some(Action::Deal(hands())));
+        assert_eq!(game.phase(), Phase::Dealing);
+    }
+}
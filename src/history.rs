@@ -0,0 +1,59 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use card::Card;
+use cards::Cards;
+use passing::PassDirection;
+use table::{PerSeat, Seat};
+
+/// A completed trick.
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Trick {
+    /// The seat that led the trick
+    pub leader: Seat,
+    /// The seats and cards in the order they were played
+    pub plays: Vec<(Seat, Card)>,
+    /// The seat that won the trick
+    pub winner: Seat,
+    /// Whether a nine of the led suit extended the trick to a second round
+    pub nined: bool,
+}
+
+impl Trick {
+    pub fn lead(&self) -> Card {
+        self.plays[0].1
+    }
+
+    pub fn cards(&self) -> Cards {
+        self.plays.iter().map(|&(_, card)| card).collect()
+    }
+}
+
+/// Everything that happened in a finished hand.
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HandRecord {
+    /// The hands as dealt, before the pass
+    pub dealt: PerSeat<Cards>,
+    pub pass_direction: PassDirection,
+    /// The cards each seat passed
+    pub passed: PerSeat<Cards>,
+    /// The cards that were charged
+    pub charged: Cards,
+    /// Every trick, in the order played
+    pub tricks: Vec<Trick>,
+    /// The score for each seat
+    pub scores: PerSeat<i32>,
+}
+
+impl HandRecord {
+    /// The cards each seat won.
+    pub fn won(&self) -> PerSeat<Cards> {
+        let mut won = PerSeat::new(self.dealt.players());
+        for trick in &self.tricks {
+            won[trick.winner] |= trick.cards();
+        }
+        won
+    }
+}
@@ -0,0 +1,43 @@
+//! Card model, rules, scoring and solver for turbo hearts.
+
+#[macro_use]
+extern crate bitflags;
+
+extern crate rand;
+
+#[cfg(feature = "serde")]
+extern crate serde;
+
+mod card;
+mod cards;
+mod charging;
+mod game;
+mod history;
+mod matches;
+mod passing;
+mod rules;
+mod scoring;
+#[cfg(feature = "serde")]
+mod serde_impls;
+mod solver;
+mod state;
+mod table;
+mod transposition;
+mod view;
+
+pub use card::{Card, Rank, Suit};
+pub use cards::{Cards, CardsIter, ParseCardsError, ParseCardsErrorKind};
+pub use charging::{ChargeTiming, ChargingRound};
+pub use game::{Action, Claim, Game, GameEvent, GameObserver, Phase, RuleViolation};
+pub use history::{HandRecord, Trick};
+pub use matches::{HandResult, Match, MatchEnd};
+pub use passing::PassDirection;
+pub use rules::{distinct_plays, holder_of, is_nined, legal_plays, trick_winner, RuleSet};
+pub use scoring::{money, score, ScoreBreakdown};
+pub use solver::{
+    claim_holds, claim_play, opt_hand, opt_inner, opt_post_charge, solve, solve_detailed,
+    solve_limited, LimitedSolve, SearchLimit, SearchMode, SolveResult, SolverOptions,
+};
+pub use state::{deal_hands, deal_hands_for, deck, FullState};
+pub use table::{PerSeat, Seat, Seats, MAX_PLAYERS, MIN_PLAYERS};
+pub use view::PlayerView;
@@ -0,0 +1,298 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use game::{Action, Game, GameEvent, GameObserver, RuleViolation};
+use history::HandRecord;
+use passing::PassDirection;
+use rules::RuleSet;
+use scoring::money;
+use state::deal_hands_for;
+use table::{PerSeat, Seat};
+
+/// When a match is over.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MatchEnd {
+    /// After this many hands
+    Hands(usize),
+    /// After the hand in which some seat's total reaches this much money,
+    /// won or lost
+    Target(i32),
+}
+
+/// The outcome of one hand of a match.
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HandResult {
+    pub dealer: Seat,
+    pub record: HandRecord,
+    /// The money each seat won (or, if negative, lost) on the hand
+    pub money: PerSeat<i32>,
+}
+
+/// A session of successive hands. The deal moves one seat to the left each
+/// hand, the pass rotates as `PassDirection::for_hand` gives for the number
+/// of players, and the money from every hand is added up for the final
+/// settlement.
+pub struct Match {
+    end: MatchEnd,
+    first_dealer: Seat,
+    rules: RuleSet,
+    game: Game,
+    hands: Vec<HandResult>,
+    totals: PerSeat<i32>,
+    observers: Vec<Box<dyn GameObserver>>,
+}
+
+impl Match {
+    /// A match dealt first by `first_dealer`. A `MatchEnd::Target` must be
+    /// more than zero, or the match would be over before it began.
+    pub fn new(end: MatchEnd, first_dealer: Seat, rules: RuleSet) -> Match {
+        if let MatchEnd::Target(target) = end {
+            assert!(target > 0, "the target must be more than zero");
+        }
+        Match {
+            end,
+            first_dealer,
+            rules,
+            game: Game::new(
+                first_dealer,
+                PassDirection::for_hand(0, rules.players),
+                rules,
+            ),
+            hands: Vec::new(),
+            totals: PerSeat::new(rules.players),
+            observers: Vec::new(),
+        }
+    }
+
+    /// The hand in progress, or the final hand once the match is over.
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// The results of the hands completed so far, in order.
+    pub fn hands(&self) -> &[HandResult] {
+        &self.hands
+    }
+
+    /// The money each seat has won or lost over the completed hands.
+    pub fn totals(&self) -> PerSeat<i32> {
+        self.totals
+    }
+
+    pub fn is_over(&self) -> bool {
+        match self.end {
+            MatchEnd::Hands(count) => self.hands.len() >= count,
+            MatchEnd::Target(target) => self.totals.iter().any(|total| total.abs() >= target),
+        }
+    }
+
+    /// Registers an observer to be told of the events from every action
+    /// applied to this and all later hands of the match.
+    pub fn add_observer(&mut self, observer: Box<dyn GameObserver>) {
+        self.observers.push(observer);
+    }
+
+    /// Deals a random hand.
+    pub fn deal(&mut self) -> Result<Vec<GameEvent>, RuleViolation> {
+        self.apply(Action::Deal(deal_hands_for(self.rules.players)))
+    }
+
+    /// Applies an action to the hand in progress. Completing a hand settles
+    /// its money and, unless the match is over, starts the next hand.
+    pub fn apply(&mut self, action: Action) -> Result<Vec<GameEvent>, RuleViolation> {
+        if self.is_over() {
+            return Err(RuleViolation::MatchOver);
+        }
+        let events = self.game.apply(action)?;
+        for observer in &mut self.observers {
+            for event in &events {
+                observer.on_event(event);
+            }
+        }
+        if let Some(record) = self.game.record() {
+            self.finish_hand(record);
+        }
+        Ok(events)
+    }
+
+    fn finish_hand(&mut self, record: HandRecord) {
+        let won = record.won();
+        let hand_money = PerSeat::from_fn(self.rules.players, |seat| {
+            money(won, record.charged, seat, &self.rules)
+        });
+        for seat in hand_money.seats() {
+            self.totals[seat] += hand_money[seat];
+        }
+        self.hands.push(HandResult {
+            dealer: self.game.dealer(),
+            record,
+            money: hand_money,
+        });
+        if !self.is_over() {
+            let hand = self.hands.len();
+            self.game = Game::new(
+                self.first_dealer.to_left(hand),
+                PassDirection::for_hand(hand, self.rules.players),
+                self.rules,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cards::Cards;
+    use charging::ChargeTiming;
+    use game::Phase;
+    use state::deck;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /// Plays out the hand in progress: everyone passes their three lowest
+    /// cards, charges nothing and plays their lowest legal card.
+    fn play_hand(session: &mut Match) {
+        session.deal().unwrap();
+        while session.game().phase() != Phase::Playing {
+            let seat = session.game().next_to_act().unwrap();
+            let action = match session.game().phase() {
+                Phase::Passing => Action::Pass {
+                    seat,
+                    cards: session.game().state().hand[seat].iter().take(3).collect(),
+                },
+                _ => Action::Charge {
+                    seat,
+                    cards: Cards::empty(),
+                },
+            };
+            session.apply(action).unwrap();
+        }
+        let hands = session.hands().len();
+        while session.hands().len() == hands {
+            let seat = session.game().next_to_act().unwrap();
+            let card = session.game().legal_plays().min();
+            session.apply(Action::Play { seat, card }).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_match_rotation() {
+        let mut session = Match::new(MatchEnd::Hands(5), Seat::new(2, 4), RuleSet::default());
+        for hand in 0..5 {
+            assert!(!session.is_over());
+            assert_eq!(session.game().dealer(), Seat::new(2, 4).to_left(hand));
+            assert_eq!(
+                session.game().pass_direction(),
+                PassDirection::for_hand(hand, 4)
+            );
+            play_hand(&mut session);
+        }
+        assert!(session.is_over());
+        assert_eq!(session.game().phase(), Phase::Complete);
+        assert_eq!(session.deal(), Err(RuleViolation::MatchOver));
+
+        let hands = session.hands();
+        assert_eq!(hands.len(), 5);
+        let directions: Vec<_> = hands.iter().map(|h| h.record.pass_direction).collect();
+        assert_eq!(
+            directions,
+            [
+                PassDirection::Left,
+                PassDirection::Right,
+                PassDirection::Across,
+                PassDirection::Keeper,
+                PassDirection::Left,
+            ]
+        );
+        let mut totals = PerSeat::new(4);
+        for hand in hands {
+            assert_eq!(hand.money.iter().sum::<i32>(), 0);
+            for (total, money) in totals.iter_mut().zip(&hand.money) {
+                *total += money;
+            }
+        }
+        assert_eq!(session.totals(), totals);
+    }
+
+    #[test]
+    fn test_match_charging_order() {
+        let mut session = Match::new(
+            MatchEnd::Hands(2),
+            Seat::new(1, 4),
+            RuleSet {
+                charge_timing: ChargeTiming::BeforePass,
+                ..RuleSet::default()
+            },
+        );
+        session.deal().unwrap();
+        assert_eq!(session.game().phase(), Phase::Charging);
+        assert_eq!(session.game().next_to_act(), Some(Seat::new(2, 4)));
+    }
+
+    #[test]
+    fn test_match_players() {
+        for players in 3..7 {
+            let rules = RuleSet {
+                players,
+                ..RuleSet::default()
+            };
+            let mut session = Match::new(MatchEnd::Hands(players), Seat::new(1, players), rules);
+            for hand in 0..players {
+                assert_eq!(session.game().dealer(), Seat::new(1, players).to_left(hand));
+                play_hand(&mut session);
+            }
+            assert!(session.is_over());
+            for hand in session.hands() {
+                assert_eq!(hand.money.players(), players);
+                assert_eq!(hand.money.iter().sum::<i32>(), 0);
+                let won = hand.record.won();
+                let taken = won.iter().fold(Cards::empty(), |taken, &won| taken | won);
+                assert_eq!(taken, deck(players));
+            }
+        }
+    }
+
+    #[test]
+    fn test_match_observer() {
+        let scored = Rc::new(Cell::new(0));
+        let mut session = Match::new(MatchEnd::Hands(3), Seat::new(0, 4), RuleSet::default());
+        let count = Rc::clone(&scored);
+        session.add_observer(Box::new(move |event: &GameEvent| {
+            if let GameEvent::HandScored { .. } = *event {
+                count.set(count.get() + 1);
+            }
+        }));
+        while !session.is_over() {
+            play_hand(&mut session);
+        }
+        assert_eq!(scored.get(), 3);
+    }
+
+    #[test]
+    fn test_match_target() {
+        let mut session = Match::new(MatchEnd::Target(1), Seat::new(0, 4), RuleSet::default());
+        while !session.is_over() {
+            play_hand(&mut session);
+        }
+        let last = session.hands().last().unwrap();
+        assert!(last.money.iter().any(|&money| money != 0));
+        assert!(session.totals().iter().any(|total| total.abs() >= 1));
+        assert_eq!(session.deal(), Err(RuleViolation::MatchOver));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_match_zero_target() {
+        Match::new(MatchEnd::Target(0), Seat::new(0, 4), RuleSet::default());
+    }
+
+    #[test]
+    fn test_match_no_hands() {
+        let mut session = Match::new(MatchEnd::Hands(0), Seat::new(0, 4), RuleSet::default());
+        assert!(session.is_over());
+        assert_eq!(session.deal(), Err(RuleViolation::MatchOver));
+        assert_eq!(session.game().phase(), Phase::Dealing);
+    }
+}
@@ -0,0 +1,95 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use table::Seat;
+
+/// Where each player sends their three cards before play. The direction
+/// rotates left, right, across and then a keeper hand with no pass, skipping
+/// across when there is an odd number of players.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PassDirection {
+    Left,
+    Right,
+    Across,
+    Keeper,
+}
+
+impl PassDirection {
+    pub const ROTATION: [PassDirection; 4] = [
+        PassDirection::Left,
+        PassDirection::Right,
+        PassDirection::Across,
+        PassDirection::Keeper,
+    ];
+
+    pub const ODD_ROTATION: [PassDirection; 3] = [
+        PassDirection::Left,
+        PassDirection::Right,
+        PassDirection::Keeper,
+    ];
+
+    /// The direction for the given hand of a match, counting from zero.
+    pub fn for_hand(hand: usize, players: usize) -> PassDirection {
+        if players % 2 == 0 {
+            PassDirection::ROTATION[hand % 4]
+        } else {
+            PassDirection::ODD_ROTATION[hand % 3]
+        }
+    }
+
+    /// The seat that receives the cards passed by `seat`.
+    pub fn target(self, seat: Seat) -> Seat {
+        match self {
+            PassDirection::Left => seat.left(),
+            PassDirection::Right => seat.right(),
+            PassDirection::Across => seat.across(),
+            PassDirection::Keeper => seat,
+        }
+    }
+
+    /// The seat whose cards `seat` receives.
+    pub fn source(self, seat: Seat) -> Seat {
+        match self {
+            PassDirection::Left => seat.right(),
+            PassDirection::Right => seat.left(),
+            PassDirection::Across => seat.to_left(seat.players() - seat.players() / 2),
+            PassDirection::Keeper => seat,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation() {
+        assert_eq!(PassDirection::for_hand(0, 4), PassDirection::Left);
+        assert_eq!(PassDirection::for_hand(3, 4), PassDirection::Keeper);
+        assert_eq!(PassDirection::for_hand(5, 4), PassDirection::Right);
+        assert_eq!(PassDirection::for_hand(2, 3), PassDirection::Keeper);
+        assert_eq!(PassDirection::for_hand(4, 5), PassDirection::Right);
+        assert_eq!(PassDirection::for_hand(2, 6), PassDirection::Across);
+        for players in 3..7 {
+            for &direction in &PassDirection::ROTATION {
+                for seat in Seat::all(players) {
+                    assert_eq!(direction.source(direction.target(seat)), seat);
+                }
+            }
+        }
+        let seat = |index| Seat::new(index, 4);
+        assert_eq!(PassDirection::Left.target(seat(3)), seat(0));
+        assert_eq!(PassDirection::Right.target(seat(0)), seat(3));
+        assert_eq!(PassDirection::Across.target(seat(1)), seat(3));
+        assert_eq!(PassDirection::Keeper.target(seat(2)), seat(2));
+        assert_eq!(
+            PassDirection::Right.target(Seat::new(0, 3)),
+            Seat::new(2, 3)
+        );
+        assert_eq!(
+            PassDirection::Across.target(Seat::new(4, 6)),
+            Seat::new(1, 6)
+        );
+    }
+}
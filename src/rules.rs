@@ -0,0 +1,688 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use card::Card;
+use cards::Cards;
+use charging::ChargeTiming;
+use table::{PerSeat, Seat};
+
+/// The house rules a hand is played under. The default is turbo hearts as
+/// the solver has always played it; `classic` and `omnibus` give the common
+/// variants.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RuleSet {
+    /// The number of players at the table, from three to six
+    pub players: usize,
+    /// The cards that score points: the hearts and the queen of spades, plus
+    /// the jack of diamonds if it is in play
+    pub points: Cards,
+    /// Taking the ten of clubs doubles the score
+    pub ten_of_clubs_doubles: bool,
+    /// There is a charging round
+    pub charging: bool,
+    /// A nine of the led suit extends the trick to a second round
+    pub nines_extend_tricks: bool,
+    /// Points may not be played to the first trick while other cards are held
+    pub no_points_on_first_trick: bool,
+    /// Hearts may not be led until a heart has been taken
+    pub hearts_must_be_broken: bool,
+    /// A charged card may not be led until its suit has been led, nor played
+    /// to the first trick of its suit while other cards in the suit are held
+    pub charged_cards_wait: bool,
+    /// Taking every heart and the queen of spades negates the score
+    pub shoot_the_moon: bool,
+    /// Whether charging happens before or after the pass
+    pub charge_timing: ChargeTiming,
+}
+
+impl RuleSet {
+    /// Plain hearts: 26 points, the queen of spades is 13, and shooting the
+    /// moon is allowed.
+    pub fn classic() -> RuleSet {
+        RuleSet {
+            players: 4,
+            points: Cards::HEARTS | Cards::QUEEN_SPADES,
+            ten_of_clubs_doubles: false,
+            charging: false,
+            nines_extend_tricks: false,
+            no_points_on_first_trick: true,
+            hearts_must_be_broken: true,
+            charged_cards_wait: false,
+            shoot_the_moon: true,
+            charge_timing: ChargeTiming::AfterPass,
+        }
+    }
+
+    /// Classic hearts where the jack of diamonds is worth -10.
+    pub fn omnibus() -> RuleSet {
+        RuleSet {
+            points: Cards::POINTS,
+            ..RuleSet::classic()
+        }
+    }
+
+    /// Omnibus hearts with the ten of clubs, charging and nines.
+    pub fn turbo() -> RuleSet {
+        RuleSet {
+            players: 4,
+            points: Cards::POINTS,
+            ten_of_clubs_doubles: true,
+            charging: true,
+            nines_extend_tricks: true,
+            no_points_on_first_trick: true,
+            hearts_must_be_broken: true,
+            charged_cards_wait: true,
+            shoot_the_moon: true,
+            charge_timing: ChargeTiming::AfterPass,
+        }
+    }
+
+    /// The cards that may be charged, or empty if there is no charging.
+    pub fn chargeable(&self) -> Cards {
+        if self.charging {
+            Cards::CHARGEABLE
+        } else {
+            Cards::empty()
+        }
+    }
+}
+
+impl Default for RuleSet {
+    fn default() -> RuleSet {
+        RuleSet::turbo()
+    }
+}
+
+pub fn trick_winner(trick: Cards, lead: Card) -> Card {
+    (trick & lead.suit()).max()
+}
+
+pub fn is_nined(trick: Cards, lead: Card) -> bool {
+    !(Cards::NINES & trick & lead.suit()).is_empty()
+}
+
+/// The seat holding `card`, if anyone does.
+pub fn holder_of(hand: PerSeat<Cards>, card: Card) -> Option<Seat> {
+    hand.seats()
+        .find(|&seat| hand[seat].contains(card.as_cards()))
+}
+
+pub fn legal_plays(
+    hand: Cards,
+    charged: Cards,
+    led_suits: Cards,
+    lead: Option<Card>,
+    hearts_broken: bool,
+    rules: &RuleSet,
+) -> Cards {
+    let mut plays = hand;
+    let suit = lead.map(Card::suit).unwrap_or(Cards::empty());
+
+    // If this is the first trick
+    if led_suits.is_empty() {
+        // and you have the two of clubs
+        if plays.intersects(Cards::TWO_CLUBS) {
+            // you must play it
+            return Cards::TWO_CLUBS;
+        }
+
+        // If points are banned from the first trick
+        if rules.no_points_on_first_trick {
+            // and you have a non-point card
+            if plays.intersects(!rules.points) {
+                // you cannot play a point
+                plays -= rules.points;
+
+            // otherwise, if you have the jack of diamonds
+            } else if plays.intersects(Cards::JACK_DIAMONDS) {
+                // you must play it
+                return Cards::JACK_DIAMONDS;
+
+            // otherwise, if you have the queen of spades
+            } else if plays.intersects(Cards::QUEEN_SPADES) {
+                // you must play it
+                return Cards::QUEEN_SPADES;
+            }
+        }
+    }
+
+    // If you're leading the trick
+    if suit.is_empty() {
+        // and hearts must be broken but are not, and you have a non-heart
+        if rules.hearts_must_be_broken && !hearts_broken && plays.intersects(!Cards::HEARTS) {
+            // you may not lead hearts
+            plays -= Cards::HEARTS;
+        }
+
+        // If charged cards wait, and you have a non-charged playable card
+        if rules.charged_cards_wait && plays.intersects(!(Cards::CHARGEABLE & charged)) {
+            // for each chargeable card
+            for card in &[
+                Cards::QUEEN_SPADES,
+                Cards::ACE_HEARTS,
+                Cards::TEN_CLUBS,
+                Cards::JACK_DIAMONDS,
+            ] {
+                // if that card is charged, and its suit has not been led
+                if charged.intersects(*card) && !led_suits.intersects(*card) {
+                    // you may not lead it
+                    plays -= *card;
+                }
+            }
+        }
+
+    // otherwise
+    } else {
+        // if you have a card in suit
+        if plays.intersects(suit) {
+            // you must play a card in suit
+            plays &= suit;
+
+            // and if charged cards wait, this is the first lead of the suit
+            // and you have multiple plays
+            if rules.charged_cards_wait && !led_suits.intersects(suit) && plays.len() > 1 {
+                // you may not play the charged card in suit
+                plays -= charged & suit;
+            }
+        }
+    }
+
+    plays
+}
+
+/// Remove from plays all cards that have the same trick taking and scoring
+/// potential as some larger card in plays.
+///
+/// Generally, two cards will be equivalent if they are the same suit and all
+/// cards between them have already been played, or are in hand.
+///
+/// However, special cards (nines if they extend tricks, QS, JD if it scores,
+/// TC if it doubles, and AH if charged) are not equivalent to any other card
+/// AND if they have not been played, no cards that span them are equivalent
+/// either.
+pub fn distinct_plays(plays: Cards, played: Cards, charged: Cards, rules: &RuleSet) -> Cards {
+    let nines = if rules.nines_extend_tricks {
+        Cards::NINES
+    } else {
+        Cards::empty()
+    };
+    let ten = if rules.ten_of_clubs_doubles {
+        Cards::TEN_CLUBS
+    } else {
+        Cards::empty()
+    };
+    let special = nines
+        | ten
+        | (rules.points & (Cards::QUEEN_SPADES | Cards::JACK_DIAMONDS))
+        | (charged & Cards::ACE_HEARTS);
+    let special_plays = plays & special;
+    let mut magic = (plays - special_plays).bits();
+    let equivalent_blocks = magic | played.bits();
+    for _ in 0..11 {
+        magic = (magic | (magic >> 1)) & equivalent_blocks;
+    }
+    magic += ((!magic) << 1) | 0x0001_0001_0001_0001;
+    special_plays | Cards::from_bits(plays.bits() & (magic >> 1)).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distinct_plays() {
+        assert_eq!(
+            distinct_plays(
+                Cards::parse("AQT8642S KJ9753H AQT8642D KJ9753C").unwrap(),
+                Cards::parse("KJ9753S AQT8642H KJ9753D AQT8642C").unwrap(),
+                Cards::empty(),
+                &RuleSet::default()
+            ),
+            Cards::parse("AQTS K97H AD K97C").unwrap()
+        );
+        assert_eq!(
+            distinct_plays(
+                Cards::parse("K7S").unwrap(),
+                Cards::parse("QJT98S").unwrap(),
+                Cards::empty(),
+                &RuleSet::default()
+            ),
+            Cards::parse("KS").unwrap()
+        );
+        assert_eq!(
+            distinct_plays(
+                Cards::parse("Q7S").unwrap(),
+                Cards::parse("JT98S").unwrap(),
+                Cards::empty(),
+                &RuleSet::default()
+            ),
+            Cards::parse("Q7S").unwrap()
+        );
+        println!(
+            "{}",
+            distinct_plays(
+                Cards::parse("AKH").unwrap(),
+                Cards::empty(),
+                Cards::empty(),
+                &RuleSet::default()
+            )
+        );
+        assert_eq!(
+            distinct_plays(
+                Cards::parse("AKH").unwrap(),
+                Cards::empty(),
+                Cards::empty(),
+                &RuleSet::default()
+            ),
+            Cards::parse("AH").unwrap()
+        );
+        assert_eq!(
+            distinct_plays(
+                Cards::parse("AKH").unwrap(),
+                Cards::empty(),
+                Cards::ACE_HEARTS,
+                &RuleSet::default()
+            ),
+            Cards::parse("AKH").unwrap()
+        );
+        assert_eq!(
+            distinct_plays(
+                Cards::parse("A2D").unwrap(),
+                Cards::parse("KQJT9876543D").unwrap(),
+                Cards::empty(),
+                &RuleSet::default()
+            ),
+            Cards::parse("AD").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_legal_plays_lead() {
+        assert_eq!(
+            legal_plays(
+                Cards::parse("AQ54S 543H AKQ2C 83D").unwrap(),
+                Cards::empty(),
+                Cards::empty(),
+                None,
+                false,
+                &RuleSet::default()
+            ),
+            Cards::parse("2C").unwrap()
+        );
+        assert_eq!(
+            legal_plays(
+                Cards::parse("AQ54S 543H AKC 83D").unwrap(),
+                Cards::empty(),
+                Cards::CLUBS,
+                None,
+                false,
+                &RuleSet::default()
+            ),
+            Cards::parse("AQ54S AKC 83D").unwrap()
+        );
+        assert_eq!(
+            legal_plays(
+                Cards::parse("AT543H").unwrap(),
+                Cards::empty(),
+                Cards::SPADES | Cards::DIAMONDS | Cards::CLUBS,
+                None,
+                false,
+                &RuleSet::default()
+            ),
+            Cards::parse("AT543H").unwrap()
+        );
+        assert_eq!(
+            legal_plays(
+                Cards::parse("AQ54S 543H AKC 83D").unwrap(),
+                Cards::QUEEN_SPADES,
+                Cards::DIAMONDS | Cards::CLUBS,
+                None,
+                true,
+                &RuleSet::default()
+            ),
+            Cards::parse("A54S 543H AKC 83D").unwrap()
+        );
+        assert_eq!(
+            legal_plays(
+                Cards::parse("AQ54S 543H AKC 83D").unwrap(),
+                Cards::QUEEN_SPADES,
+                Cards::SPADES | Cards::CLUBS,
+                None,
+                true,
+                &RuleSet::default()
+            ),
+            Cards::parse("AQ54S 543H AKC 83D").unwrap()
+        );
+        assert_eq!(
+            legal_plays(
+                Cards::parse("AKQJT9H JD").unwrap(),
+                Cards::JACK_DIAMONDS,
+                Cards::SPADES | Cards::CLUBS,
+                None,
+                false,
+                &RuleSet::default()
+            ),
+            Cards::parse("JD").unwrap()
+        );
+        assert_eq!(
+            legal_plays(
+                Cards::parse("AKQJT9H JD").unwrap(),
+                Cards::JACK_DIAMONDS,
+                Cards::SPADES | Cards::CLUBS | Cards::DIAMONDS,
+                None,
+                false,
+                &RuleSet::default()
+            ),
+            Cards::parse("JD").unwrap()
+        );
+        assert_eq!(
+            legal_plays(
+                Cards::parse("AKQJT9H JD").unwrap(),
+                Cards::JACK_DIAMONDS,
+                Cards::SPADES | Cards::CLUBS,
+                None,
+                true,
+                &RuleSet::default()
+            ),
+            Cards::parse("AKQJT9H").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_legal_plays_follow_first_trick() {
+        assert_eq!(
+            legal_plays(
+                Cards::parse("AQ54S 543H AKQ3C 83D").unwrap(),
+                Cards::empty(),
+                Cards::empty(),
+                Some(Cards::parse("2C").unwrap().max()),
+                false,
+                &RuleSet::default()
+            ),
+            Cards::parse("AKQ3C").unwrap()
+        );
+        assert_eq!(
+            legal_plays(
+                Cards::parse("AQ5432S 8543H T83D").unwrap(),
+                Cards::empty(),
+                Cards::empty(),
+                Some(Cards::parse("2C").unwrap().max()),
+                false,
+                &RuleSet::default()
+            ),
+            Cards::parse("A5432S T83D").unwrap()
+        );
+        assert_eq!(
+            legal_plays(
+                Cards::parse("QS AKQJT987654H JD").unwrap(),
+                Cards::empty(),
+                Cards::empty(),
+                Some(Cards::parse("2C").unwrap().max()),
+                false,
+                &RuleSet::default()
+            ),
+            Cards::parse("JD").unwrap()
+        );
+        assert_eq!(
+            legal_plays(
+                Cards::parse("QS AKQJT9876542H").unwrap(),
+                Cards::QUEEN_SPADES,
+                Cards::empty(),
+                Some(Cards::parse("2C").unwrap().max()),
+                false,
+                &RuleSet::default()
+            ),
+            Cards::parse("QS").unwrap()
+        );
+        assert_eq!(
+            legal_plays(
+                Cards::parse("AKQJT98765432H").unwrap(),
+                Cards::QUEEN_SPADES,
+                Cards::empty(),
+                Some(Cards::parse("2C").unwrap().max()),
+                false,
+                &RuleSet::default()
+            ),
+            Cards::parse("AKQJT98765432H").unwrap()
+        );
+        assert_eq!(
+            legal_plays(
+                Cards::parse("AKQJT98765432H").unwrap(),
+                Cards::ACE_HEARTS,
+                Cards::empty(),
+                Some(Cards::parse("2C").unwrap().max()),
+                false,
+                &RuleSet::default()
+            ),
+            Cards::parse("AKQJT98765432H").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_legal_plays_follow() {
+        assert_eq!(
+            legal_plays(
+                Cards::parse("AQS 54H AQ3C 83D").unwrap(),
+                Cards::QUEEN_SPADES,
+                Cards::CLUBS | Cards::DIAMONDS,
+                Some(Cards::parse("7C").unwrap().max()),
+                false,
+                &RuleSet::default()
+            ),
+            Cards::parse("AQ3C").unwrap()
+        );
+        assert_eq!(
+            legal_plays(
+                Cards::parse("AQS 54H 83D").unwrap(),
+                Cards::QUEEN_SPADES,
+                Cards::CLUBS | Cards::DIAMONDS,
+                Some(Cards::parse("7C").unwrap().max()),
+                false,
+                &RuleSet::default()
+            ),
+            Cards::parse("AQS 54H 83D").unwrap()
+        );
+        assert_eq!(
+            legal_plays(
+                Cards::parse("AQS 54H AQ3C 83D").unwrap(),
+                Cards::QUEEN_SPADES,
+                Cards::CLUBS | Cards::DIAMONDS,
+                Some(Cards::parse("7S").unwrap().max()),
+                false,
+                &RuleSet::default()
+            ),
+            Cards::parse("AS").unwrap()
+        );
+        assert_eq!(
+            legal_plays(
+                Cards::parse("QS 54H AQ3C 83D").unwrap(),
+                Cards::QUEEN_SPADES,
+                Cards::CLUBS | Cards::DIAMONDS,
+                Some(Cards::parse("7S").unwrap().max()),
+                false,
+                &RuleSet::default()
+            ),
+            Cards::parse("QS").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_legal_plays_rule_toggles() {
+        let rules = RuleSet {
+            no_points_on_first_trick: false,
+            ..RuleSet::default()
+        };
+        assert_eq!(
+            legal_plays(
+                Cards::parse("AQ5432S 8543H T83D").unwrap(),
+                Cards::empty(),
+                Cards::empty(),
+                Some(Card::TWO_CLUBS),
+                false,
+                &rules
+            ),
+            Cards::parse("AQ5432S 8543H T83D").unwrap()
+        );
+        let rules = RuleSet {
+            hearts_must_be_broken: false,
+            ..RuleSet::default()
+        };
+        assert_eq!(
+            legal_plays(
+                Cards::parse("AQ54S 543H AKC 83D").unwrap(),
+                Cards::empty(),
+                Cards::CLUBS,
+                None,
+                false,
+                &rules
+            ),
+            Cards::parse("AQ54S 543H AKC 83D").unwrap()
+        );
+        let rules = RuleSet {
+            charged_cards_wait: false,
+            ..RuleSet::default()
+        };
+        assert_eq!(
+            legal_plays(
+                Cards::parse("AQ54S 543H AKC 83D").unwrap(),
+                Cards::QUEEN_SPADES,
+                Cards::DIAMONDS | Cards::CLUBS,
+                None,
+                true,
+                &rules
+            ),
+            Cards::parse("AQ54S 543H AKC 83D").unwrap()
+        );
+        assert_eq!(
+            legal_plays(
+                Cards::parse("QS 54H AQ3C 83D").unwrap(),
+                Cards::QUEEN_SPADES,
+                Cards::CLUBS | Cards::DIAMONDS,
+                Some(Cards::parse("7S").unwrap().max()),
+                false,
+                &rules
+            ),
+            Cards::parse("QS").unwrap()
+        );
+        assert_eq!(
+            legal_plays(
+                Cards::parse("AQS 54H AQ3C 83D").unwrap(),
+                Cards::QUEEN_SPADES,
+                Cards::CLUBS | Cards::DIAMONDS,
+                Some(Cards::parse("7S").unwrap().max()),
+                false,
+                &rules
+            ),
+            Cards::parse("AQS").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_distinct_plays_without_nines() {
+        let rules = RuleSet {
+            nines_extend_tricks: false,
+            ..RuleSet::default()
+        };
+        assert_eq!(
+            distinct_plays(
+                Cards::parse("T98S").unwrap(),
+                Cards::empty(),
+                Cards::empty(),
+                &RuleSet::default()
+            ),
+            Cards::parse("T98S").unwrap()
+        );
+        assert_eq!(
+            distinct_plays(
+                Cards::parse("T98S").unwrap(),
+                Cards::empty(),
+                Cards::empty(),
+                &rules
+            ),
+            Cards::parse("TS").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_presets() {
+        assert_eq!(RuleSet::default(), RuleSet::turbo());
+        assert_eq!(RuleSet::turbo().chargeable(), Cards::CHARGEABLE);
+        assert_eq!(RuleSet::classic().chargeable(), Cards::empty());
+        assert_eq!(
+            legal_plays(
+                Cards::parse("QS AKQJT98765H JD").unwrap(),
+                Cards::empty(),
+                Cards::empty(),
+                Some(Card::TWO_CLUBS),
+                false,
+                &RuleSet::classic()
+            ),
+            Cards::parse("JD").unwrap()
+        );
+        assert_eq!(
+            legal_plays(
+                Cards::parse("QS AKQJT987654H").unwrap(),
+                Cards::empty(),
+                Cards::empty(),
+                Some(Card::TWO_CLUBS),
+                false,
+                &RuleSet::omnibus()
+            ),
+            Cards::parse("QS").unwrap()
+        );
+        assert_eq!(
+            distinct_plays(
+                Cards::parse("QJTD").unwrap(),
+                Cards::empty(),
+                Cards::empty(),
+                &RuleSet::classic()
+            ),
+            Cards::parse("QD").unwrap()
+        );
+        assert_eq!(
+            distinct_plays(
+                Cards::parse("QJTD").unwrap(),
+                Cards::empty(),
+                Cards::empty(),
+                &RuleSet::omnibus()
+            ),
+            Cards::parse("QJTD").unwrap()
+        );
+        assert_eq!(
+            distinct_plays(
+                Cards::parse("JT9C").unwrap(),
+                Cards::empty(),
+                Cards::empty(),
+                &RuleSet::omnibus()
+            ),
+            Cards::parse("JC").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_holder_of() {
+        let hand = PerSeat::from([
+            Cards::parse("AKS").unwrap(),
+            Cards::parse("2C").unwrap(),
+            Cards::parse("QH").unwrap(),
+        ]);
+        assert_eq!(holder_of(hand, Card::TWO_CLUBS), Some(Seat::new(1, 3)));
+        assert_eq!(
+            holder_of(hand, "QH".parse().unwrap()),
+            Some(Seat::new(2, 3))
+        );
+        assert_eq!(holder_of(hand, "QS".parse().unwrap()), None);
+    }
+
+    #[test]
+    fn test_trick_winner() {
+        assert_eq!(
+            trick_winner(
+                Cards::parse("A8S 96H").unwrap(),
+                Cards::parse("6H").unwrap().max()
+            ),
+            Cards::parse("9H").unwrap().max()
+        );
+    }
+}
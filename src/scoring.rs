@@ -0,0 +1,434 @@
+use std::fmt;
+use std::ops::{Add, Sub};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use cards::Cards;
+use rules::RuleSet;
+use table::{PerSeat, Seat, MAX_PLAYERS};
+
+/// How a seat's score for a hand was reached.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ScoreBreakdown {
+    /// The number of hearts won
+    pub hearts: i32,
+    /// What each heart is worth: 1, or 2 with the ace of hearts charged
+    pub heart_value: i32,
+    /// What the queen of spades costs: 0, 13 or 26 charged
+    pub queen: i32,
+    /// What the jack of diamonds earns: 0, -10 or -20 charged
+    pub jack: i32,
+    /// What the ten of clubs multiplies everything by: 1, 2 or 4 charged
+    pub ten_multiplier: i32,
+    /// Whether every heart and the queen were won, turning them negative
+    pub shot_moon: bool,
+    pub total: i32,
+}
+
+impl fmt::Display for ScoreBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let penalties = format!(
+            "{} hearts x{} + queen {}",
+            self.hearts, self.heart_value, self.queen
+        );
+        if self.shot_moon {
+            write!(f, "moon -({})", penalties)?;
+        } else {
+            write!(f, "{}", penalties)?;
+        }
+        write!(f, " + jack {}", self.jack)?;
+        if self.ten_multiplier != 1 {
+            write!(f, ", ten x{}", self.ten_multiplier)?;
+        }
+        write!(f, " = {}", self.total)
+    }
+}
+
+/// The money `player` wins, or if negative loses, settling up with each of
+/// the other players for the difference in their scores.
+pub fn money(won: PerSeat<Cards>, charged: Cards, player: Seat, rules: &RuleSet) -> i32 {
+    Points::of(won).money(charged, player, rules)
+}
+
+pub fn score(won: Cards, charged: Cards, rules: &RuleSet) -> ScoreBreakdown {
+    let hearts = (won & Cards::HEARTS).len();
+    breakdown(hearts, won & Points::HONOURS, charged, rules)
+}
+
+/// The score for winning `hearts` hearts and the cards of `honours`, which
+/// are some of `Points::HONOURS`.
+fn breakdown(hearts: u32, honours: Cards, charged: Cards, rules: &RuleSet) -> ScoreBreakdown {
+    let hearts = hearts as i32;
+    let heart_value = if charged.intersects(Cards::ACE_HEARTS) {
+        2
+    } else {
+        1
+    };
+    let queen = match (
+        honours.intersects(rules.points & Cards::QUEEN_SPADES),
+        charged.intersects(Cards::QUEEN_SPADES),
+    ) {
+        (true, true) => 26,
+        (true, false) => 13,
+        _ => 0,
+    };
+    let jack = match (
+        honours.intersects(rules.points & Cards::JACK_DIAMONDS),
+        charged.intersects(Cards::JACK_DIAMONDS),
+    ) {
+        (true, true) => -20,
+        (true, false) => -10,
+        _ => 0,
+    };
+    let ten_multiplier = match (
+        rules.ten_of_clubs_doubles && honours.intersects(Cards::TEN_CLUBS),
+        charged.intersects(Cards::TEN_CLUBS),
+    ) {
+        (true, true) => 4,
+        (true, false) => 2,
+        _ => 1,
+    };
+    let shot_moon = rules.shoot_the_moon
+        && honours.intersects(Cards::QUEEN_SPADES)
+        && hearts == Cards::HEARTS.len() as i32;
+    let total = if shot_moon {
+        ten_multiplier * (jack - hearts * heart_value - queen)
+    } else {
+        ten_multiplier * (jack + hearts * heart_value + queen)
+    };
+    ScoreBreakdown {
+        hearts,
+        heart_value,
+        queen,
+        jack,
+        ten_multiplier,
+        shot_moon,
+        total,
+    }
+}
+
+/// What the scores depend on of the cards each seat wins: the number of
+/// hearts each wins, and which of the queen of spades, the jack of diamonds
+/// and the ten of clubs.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct Points {
+    hearts: [u8; MAX_PLAYERS],
+    /// The cards of `HONOURS` each seat wins, a bit for each of
+    /// `HONOUR_CARDS`
+    honours: [u8; MAX_PLAYERS],
+}
+
+impl Points {
+    /// The cards other than hearts that can change a score.
+    pub const HONOURS: Cards = Cards::from_bits_truncate(
+        Cards::QUEEN_SPADES.bits() | Cards::JACK_DIAMONDS.bits() | Cards::TEN_CLUBS.bits(),
+    );
+
+    const HONOUR_CARDS: [Cards; 3] = [Cards::QUEEN_SPADES, Cards::JACK_DIAMONDS, Cards::TEN_CLUBS];
+
+    /// The points in the cards each seat has won.
+    pub fn of(won: PerSeat<Cards>) -> Points {
+        let mut points = Points::default();
+        for (index, &won) in won.as_slice().iter().enumerate() {
+            points.hearts[index] = (won & Cards::HEARTS).len() as u8;
+            for (bit, &honour) in Points::HONOUR_CARDS.iter().enumerate() {
+                if won.intersects(honour) {
+                    points.honours[index] |= 1 << bit;
+                }
+            }
+        }
+        points
+    }
+
+    /// The money `player` wins, as `money` counts it.
+    pub fn money(self, charged: Cards, player: Seat, rules: &RuleSet) -> i32 {
+        let (mut all, mut mine) = (0, 0);
+        for index in 0..player.players() {
+            let honours = Points::HONOUR_CARDS
+                .iter()
+                .enumerate()
+                .filter(|&(bit, _)| self.honours[index] & 1 << bit != 0)
+                .fold(Cards::empty(), |honours, (_, &honour)| honours | honour);
+            let score = breakdown(u32::from(self.hearts[index]), honours, charged, rules).total;
+            all += score;
+            if index == player.index() {
+                mine = score;
+            }
+        }
+        all - player.players() as i32 * mine
+    }
+}
+
+/// The points of both, where `other` is won from cards these are not.
+impl Add for Points {
+    type Output = Points;
+
+    fn add(mut self, other: Points) -> Points {
+        for seat in 0..MAX_PLAYERS {
+            self.hearts[seat] += other.hearts[seat];
+            self.honours[seat] |= other.honours[seat];
+        }
+        self
+    }
+}
+
+/// The points won since `earlier`, which must have been won from some of
+/// the same cards.
+impl Sub for Points {
+    type Output = Points;
+
+    fn sub(mut self, earlier: Points) -> Points {
+        for seat in 0..MAX_PLAYERS {
+            self.hearts[seat] -= earlier.hearts[seat];
+            self.honours[seat] &= !earlier.honours[seat];
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score() {
+        let c = Cards::parse("AJT5S J63H 96D A953C").unwrap();
+        assert_eq!(score(c, Cards::empty(), &RuleSet::default()).total, 3);
+        assert_eq!(
+            score(
+                c,
+                Cards::QUEEN_SPADES | Cards::TEN_CLUBS,
+                &RuleSet::default()
+            )
+            .total,
+            3
+        );
+        assert_eq!(score(c, Cards::JACK_DIAMONDS, &RuleSet::default()).total, 3);
+        assert_eq!(score(c, Cards::ACE_HEARTS, &RuleSet::default()).total, 6);
+        assert_eq!(
+            score(c, Cards::ACE_HEARTS | Cards::TEN_CLUBS, &RuleSet::default()).total,
+            6
+        );
+        let c = Cards::parse("973S T92H K7D KT74C").unwrap();
+        assert_eq!(score(c, Cards::empty(), &RuleSet::default()).total, 6);
+        assert_eq!(
+            score(
+                c,
+                Cards::QUEEN_SPADES | Cards::TEN_CLUBS,
+                &RuleSet::default()
+            )
+            .total,
+            12
+        );
+        assert_eq!(score(c, Cards::JACK_DIAMONDS, &RuleSet::default()).total, 6);
+        assert_eq!(score(c, Cards::ACE_HEARTS, &RuleSet::default()).total, 12);
+        assert_eq!(
+            score(c, Cards::ACE_HEARTS | Cards::TEN_CLUBS, &RuleSet::default()).total,
+            24
+        );
+        let c = Cards::parse("KQ6S A5H JT542D Q82C").unwrap();
+        assert_eq!(score(c, Cards::empty(), &RuleSet::default()).total, 5);
+        assert_eq!(
+            score(
+                c,
+                Cards::QUEEN_SPADES | Cards::TEN_CLUBS,
+                &RuleSet::default()
+            )
+            .total,
+            18
+        );
+        assert_eq!(
+            score(c, Cards::JACK_DIAMONDS, &RuleSet::default()).total,
+            -5
+        );
+        assert_eq!(score(c, Cards::ACE_HEARTS, &RuleSet::default()).total, 7);
+        assert_eq!(
+            score(c, Cards::ACE_HEARTS | Cards::TEN_CLUBS, &RuleSet::default()).total,
+            7
+        );
+        let c = Cards::parse("84S KQ874H AQ83D J6C").unwrap();
+        assert_eq!(score(c, Cards::empty(), &RuleSet::default()).total, 5);
+        assert_eq!(
+            score(
+                c,
+                Cards::QUEEN_SPADES | Cards::TEN_CLUBS,
+                &RuleSet::default()
+            )
+            .total,
+            5
+        );
+        assert_eq!(score(c, Cards::JACK_DIAMONDS, &RuleSet::default()).total, 5);
+        assert_eq!(score(c, Cards::ACE_HEARTS, &RuleSet::default()).total, 10);
+        assert_eq!(
+            score(c, Cards::ACE_HEARTS | Cards::TEN_CLUBS, &RuleSet::default()).total,
+            10
+        );
+        let c = Cards::HEARTS | Cards::QUEEN_SPADES;
+        assert_eq!(score(c, Cards::empty(), &RuleSet::default()).total, -26);
+        assert_eq!(
+            score(
+                c,
+                Cards::QUEEN_SPADES | Cards::TEN_CLUBS,
+                &RuleSet::default()
+            )
+            .total,
+            -39
+        );
+        assert_eq!(
+            score(c, Cards::JACK_DIAMONDS, &RuleSet::default()).total,
+            -26
+        );
+        assert_eq!(score(c, Cards::ACE_HEARTS, &RuleSet::default()).total, -39);
+        assert_eq!(
+            score(c, Cards::ACE_HEARTS | Cards::TEN_CLUBS, &RuleSet::default()).total,
+            -39
+        );
+    }
+
+    #[test]
+    fn test_score_breakdown() {
+        let c = Cards::parse("KQ6S A5H JT542D Q82C").unwrap();
+        let breakdown = score(c, Cards::ACE_HEARTS, &RuleSet::default());
+        assert_eq!(
+            breakdown,
+            ScoreBreakdown {
+                hearts: 2,
+                heart_value: 2,
+                queen: 13,
+                jack: -10,
+                ten_multiplier: 1,
+                shot_moon: false,
+                total: 7,
+            }
+        );
+        assert_eq!(
+            breakdown.to_string(),
+            "2 hearts x2 + queen 13 + jack -10 = 7"
+        );
+
+        let c = Cards::HEARTS | Cards::QUEEN_SPADES | Cards::TEN_CLUBS;
+        let breakdown = score(c, Cards::QUEEN_SPADES, &RuleSet::default());
+        assert!(breakdown.shot_moon);
+        assert_eq!(breakdown.ten_multiplier, 2);
+        assert_eq!(breakdown.total, -78);
+        assert_eq!(
+            breakdown.to_string(),
+            "moon -(13 hearts x1 + queen 26) + jack 0, ten x2 = -78"
+        );
+    }
+
+    #[test]
+    fn test_score_without_moon() {
+        let rules = RuleSet {
+            shoot_the_moon: false,
+            ..RuleSet::default()
+        };
+        let c = Cards::HEARTS | Cards::QUEEN_SPADES;
+        assert_eq!(score(c, Cards::empty(), &rules).total, 26);
+        assert_eq!(score(c, Cards::ACE_HEARTS, &rules).total, 39);
+    }
+
+    #[test]
+    fn test_score_presets() {
+        let c = Cards::parse("KQ6S A5H JT542D Q82C").unwrap();
+        assert_eq!(score(c, Cards::empty(), &RuleSet::classic()).total, 15);
+        assert_eq!(score(c, Cards::empty(), &RuleSet::omnibus()).total, 5);
+        assert_eq!(score(c, Cards::empty(), &RuleSet::turbo()).total, 5);
+        let c = Cards::parse("973S T92H K7D KT74C").unwrap();
+        assert_eq!(score(c, Cards::empty(), &RuleSet::classic()).total, 3);
+        assert_eq!(score(c, Cards::empty(), &RuleSet::omnibus()).total, 3);
+        assert_eq!(score(c, Cards::empty(), &RuleSet::turbo()).total, 6);
+        let c = Cards::HEARTS | Cards::QUEEN_SPADES | Cards::JACK_DIAMONDS;
+        assert_eq!(score(c, Cards::empty(), &RuleSet::classic()).total, -26);
+        assert_eq!(score(c, Cards::empty(), &RuleSet::omnibus()).total, -36);
+    }
+
+    #[test]
+    fn test_money() {
+        let c1 = Cards::parse("AJT5S J63H 96D A953C").unwrap();
+        let c2 = Cards::parse("973S T92H K7D KT74C").unwrap();
+        let c3 = Cards::parse("KQ6S A5H JT542D Q82C").unwrap();
+        let c4 = Cards::parse("84S KQ874H AQ83D J6C").unwrap();
+        let won = PerSeat::from([c1, c2, c3, c4]);
+        assert_eq!(
+            money(won, Cards::empty(), Seat::new(0, 4), &RuleSet::default()),
+            7
+        );
+        assert_eq!(
+            money(won, Cards::empty(), Seat::new(1, 4), &RuleSet::default()),
+            -5
+        );
+        assert_eq!(
+            money(won, Cards::empty(), Seat::new(2, 4), &RuleSet::default()),
+            -1
+        );
+        assert_eq!(
+            money(won, Cards::empty(), Seat::new(3, 4), &RuleSet::default()),
+            -1
+        );
+        assert_eq!(
+            money(won, Cards::ACE_HEARTS, Seat::new(0, 4), &RuleSet::default()),
+            11
+        );
+        assert_eq!(
+            money(won, Cards::ACE_HEARTS, Seat::new(1, 4), &RuleSet::default()),
+            -13
+        );
+        assert_eq!(
+            money(won, Cards::ACE_HEARTS, Seat::new(2, 4), &RuleSet::default()),
+            7
+        );
+        assert_eq!(
+            money(won, Cards::ACE_HEARTS, Seat::new(3, 4), &RuleSet::default()),
+            -5
+        );
+        assert_eq!(
+            money(won, Cards::TEN_CLUBS, Seat::new(0, 4), &RuleSet::default()),
+            13
+        );
+        assert_eq!(
+            money(won, Cards::TEN_CLUBS, Seat::new(1, 4), &RuleSet::default()),
+            -23
+        );
+        assert_eq!(
+            money(won, Cards::TEN_CLUBS, Seat::new(2, 4), &RuleSet::default()),
+            5
+        );
+        assert_eq!(
+            money(won, Cards::TEN_CLUBS, Seat::new(3, 4), &RuleSet::default()),
+            5
+        );
+
+        let won = PerSeat::from([c1 | c4, c2, c3]);
+        assert_eq!(
+            money(won, Cards::empty(), Seat::new(0, 3), &RuleSet::default()),
+            -5
+        );
+        assert_eq!(
+            money(won, Cards::empty(), Seat::new(1, 3), &RuleSet::default()),
+            1
+        );
+        assert_eq!(
+            money(won, Cards::empty(), Seat::new(2, 3), &RuleSet::default()),
+            4
+        );
+    }
+
+    #[test]
+    fn test_points() {
+        let c1 = Cards::parse("AJT5S J63H 96D A953C").unwrap();
+        let c2 = Cards::parse("973S T92H K7D KT74C").unwrap();
+        let c3 = Cards::parse("KQ6S A5H JT542D Q82C").unwrap();
+        let c4 = Cards::parse("84S KQ874H AQ83D J6C").unwrap();
+        let early = PerSeat::from([c1, Cards::empty(), c3, Cards::empty()]);
+        let won = PerSeat::from([c1, c2, c3, c4]);
+        let later = Points::of(won) - Points::of(early);
+        assert_eq!(
+            later,
+            Points::of(PerSeat::from([Cards::empty(), c2, Cards::empty(), c4]))
+        );
+        assert_eq!(Points::of(early) + later, Points::of(won));
+    }
+}
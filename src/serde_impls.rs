@@ -0,0 +1,223 @@
+//! Serialization for the card model.
+//!
+//! Human-readable formats get the same strings as `Display` ("QS",
+//! "AJT5S J63H"), while compact formats get the raw bit encoding: the card's
+//! index as a `u8` and the set's bits as a `u64`. Per-seat values are a
+//! sequence with one entry per player.
+
+use serde::de::{Error, Unexpected};
+use serde::ser::SerializeSeq;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use card::Card;
+use cards::Cards;
+use table::{PerSeat, Seat, MAX_PLAYERS, MIN_PLAYERS};
+
+impl Serialize for Card {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_u8(self.0)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Card {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+            s.parse().map_err(D::Error::custom)
+        } else {
+            let index = u8::deserialize(deserializer)?;
+            if index < 64 && Cards::all().bits() & (1 << index) != 0 {
+                Ok(Card(index))
+            } else {
+                Err(D::Error::invalid_value(
+                    Unexpected::Unsigned(u64::from(index)),
+                    &"a card index",
+                ))
+            }
+        }
+    }
+}
+
+impl Serialize for Cards {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_u64(self.bits())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Cards {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+            Cards::parse(&s).map_err(D::Error::custom)
+        } else {
+            let bits = u64::deserialize(deserializer)?;
+            Cards::from_bits(bits).ok_or_else(|| {
+                D::Error::invalid_value(Unexpected::Unsigned(bits), &"a set of card bits")
+            })
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Seat {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        /// A seat as it is serialized, before it is checked.
+        #[derive(Deserialize)]
+        #[serde(rename = "Seat")]
+        struct Fields {
+            index: u8,
+            players: u8,
+        }
+
+        let Fields { index, players } = Fields::deserialize(deserializer)?;
+        let (index, players) = (usize::from(index), usize::from(players));
+        if !(MIN_PLAYERS..=MAX_PLAYERS).contains(&players) {
+            return Err(D::Error::invalid_value(
+                Unexpected::Unsigned(players as u64),
+                &"three to six players",
+            ));
+        }
+        if index >= players {
+            return Err(D::Error::invalid_value(
+                Unexpected::Unsigned(index as u64),
+                &"a seat at the table",
+            ));
+        }
+        Ok(Seat::new(index, players))
+    }
+}
+
+impl<T: Serialize> Serialize for PerSeat<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.players()))?;
+        for value in self {
+            seq.serialize_element(value)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de, T: Deserialize<'de> + Copy + Default> Deserialize<'de> for PerSeat<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let values = Vec::<T>::deserialize(deserializer)?;
+        if values.len() < MIN_PLAYERS || values.len() > MAX_PLAYERS {
+            return Err(D::Error::invalid_length(
+                values.len(),
+                &"one value for each of three to six seats",
+            ));
+        }
+        Ok(PerSeat::from_slice(&values))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate bincode;
+    extern crate serde_json;
+
+    use super::*;
+    use state::FullState;
+
+    #[test]
+    fn test_card_serde() {
+        let card: Card = "QS".parse().unwrap();
+        assert_eq!(serde_json::to_string(&card).unwrap(), "\"QS\"");
+        assert_eq!(serde_json::from_str::<Card>("\"q♠\"").unwrap(), card);
+        assert!(serde_json::from_str::<Card>("\"QX\"").is_err());
+
+        let bytes = bincode::serialize(&card).unwrap();
+        assert_eq!(bytes, [card.0]);
+        assert_eq!(bincode::deserialize::<Card>(&bytes).unwrap(), card);
+        assert!(bincode::deserialize::<Card>(&[13]).is_err());
+        assert!(bincode::deserialize::<Card>(&[64]).is_err());
+    }
+
+    #[test]
+    fn test_cards_serde() {
+        let cards = Cards::parse("AJT5S J63H 96D A953C").unwrap();
+        assert_eq!(
+            serde_json::to_string(&cards).unwrap(),
+            "\"AJT5S J63H 96D A953C\""
+        );
+        assert_eq!(
+            serde_json::from_str::<Cards>("\"AJT5S J63H 96D A953C\"").unwrap(),
+            cards
+        );
+        assert_eq!(
+            serde_json::from_str::<Cards>("\"\"").unwrap(),
+            Cards::empty()
+        );
+        assert!(serde_json::from_str::<Cards>("\"AJXS\"").is_err());
+
+        let bytes = bincode::serialize(&cards).unwrap();
+        assert_eq!(bytes.len(), 8);
+        assert_eq!(bincode::deserialize::<Cards>(&bytes).unwrap(), cards);
+        let bytes = bincode::serialize(&u64::MAX).unwrap();
+        assert!(bincode::deserialize::<Cards>(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_full_state_serde() {
+        let state = FullState {
+            hand: PerSeat::from([
+                Cards::parse("AJT5S").unwrap(),
+                Cards::parse("J63H").unwrap(),
+                Cards::parse("96D").unwrap(),
+                Cards::parse("A953C").unwrap(),
+            ]),
+            won: PerSeat::from([
+                Cards::empty(),
+                Cards::HEARTS,
+                Cards::empty(),
+                Cards::empty(),
+            ]),
+            charged: Cards::QUEEN_SPADES,
+            led_suits: Cards::CLUBS,
+            trick_lead: Cards::parse("KD").unwrap(),
+            trick: Cards::parse("KQD").unwrap(),
+        };
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(
+            serde_json::to_string(&serde_json::from_str::<FullState>(&json).unwrap()).unwrap(),
+            json
+        );
+        let bytes = bincode::serialize(&state).unwrap();
+        assert_eq!(bytes.len(), 2 * 8 + 12 * 8);
+        assert_eq!(
+            bincode::serialize(&bincode::deserialize::<FullState>(&bytes).unwrap()).unwrap(),
+            bytes
+        );
+    }
+
+    #[test]
+    fn test_seat_serde() {
+        let seat = Seat::new(2, 5);
+        let json = serde_json::to_string(&seat).unwrap();
+        assert_eq!(json, "{\"index\":2,\"players\":5}");
+        assert_eq!(serde_json::from_str::<Seat>(&json).unwrap(), seat);
+        assert!(serde_json::from_str::<Seat>("{\"index\":4,\"players\":4}").is_err());
+        assert!(serde_json::from_str::<Seat>("{\"index\":0,\"players\":2}").is_err());
+        assert!(serde_json::from_str::<Seat>("{\"index\":0,\"players\":7}").is_err());
+
+        let bytes = bincode::serialize(&seat).unwrap();
+        assert_eq!(bincode::deserialize::<Seat>(&bytes).unwrap(), seat);
+        assert!(bincode::deserialize::<Seat>(&[5, 5]).is_err());
+    }
+
+    #[test]
+    fn test_per_seat_serde() {
+        let scores = PerSeat::from([3, -6, 3]);
+        let json = serde_json::to_string(&scores).unwrap();
+        assert_eq!(json, "[3,-6,3]");
+        assert_eq!(serde_json::from_str::<PerSeat<i32>>(&json).unwrap(), scores);
+        assert!(serde_json::from_str::<PerSeat<i32>>("[1,2]").is_err());
+        assert!(serde_json::from_str::<PerSeat<i32>>("[1,2,3,4,5,6,7]").is_err());
+    }
+}
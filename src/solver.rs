@@ -0,0 +1,1621 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+
+use card::Card;
+use cards::Cards;
+use game::Claim;
+use rules::{distinct_plays, holder_of, is_nined, legal_plays, trick_winner, RuleSet};
+use scoring::{money, Points};
+use table::{PerSeat, Seat};
+use transposition::{Bound, Outcome, TranspositionTable, SUITS, ZOBRIST};
+
+pub fn opt_hand(hand: PerSeat<Cards>, rules: &RuleSet) -> PerSeat<Cards> {
+    let player = holder_of(hand, Card::TWO_CLUBS).expect("nobody holds the two of clubs");
+    let mut opt_charged = Cards::empty();
+    let mut opt_won = opt_post_charge(player, hand, opt_charged, rules);
+    for seat in hand.seats() {
+        let mut opt_money = money(opt_won, opt_charged, seat, rules);
+        for card in (hand[seat] & rules.chargeable()).iter().rev() {
+            let next_charged = opt_charged | card;
+            let next_won = opt_post_charge(player, hand, next_charged, rules);
+            let next_money = money(next_won, next_charged, seat, rules);
+            if next_money > opt_money {
+                opt_charged = next_charged;
+                opt_won = next_won;
+                opt_money = next_money;
+            }
+        }
+    }
+    opt_won
+}
+
+pub fn opt_post_charge(
+    player: Seat,
+    hand: PerSeat<Cards>,
+    charged: Cards,
+    rules: &RuleSet,
+) -> PerSeat<Cards> {
+    opt_inner(
+        player,
+        hand,
+        PerSeat::new(hand.players()),
+        charged,
+        Cards::empty(),
+        None,
+        Cards::empty(),
+        rules,
+    )
+}
+
+/// How the seats are assumed to choose their plays when solving.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum SearchMode {
+    /// Every seat plays to win as much money as it can for itself. As
+    /// each seat has its own goal, no line of play can be ruled out
+    /// without searching it.
+    MaxN,
+    /// The given seat plays to win as much money as it can, and the other
+    /// seats play together to make it win as little as they can. This
+    /// answers what the seat can guarantee against any defence, and with
+    /// only two sides the search can prune lines that cannot matter.
+    Paranoid(Seat),
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn opt_inner(
+    player: Seat,
+    hand: PerSeat<Cards>,
+    won: PerSeat<Cards>,
+    charged: Cards,
+    led_suits: Cards,
+    lead: Option<Card>,
+    trick: Cards,
+    rules: &RuleSet,
+) -> PerSeat<Cards> {
+    solve(
+        &SolverOptions::default(),
+        player,
+        hand,
+        won,
+        charged,
+        led_suits,
+        lead,
+        trick,
+        rules,
+    )
+}
+
+/// How `solve` searches.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct SolverOptions {
+    /// How the seats are assumed to choose their plays
+    pub mode: SearchMode,
+    /// The number of threads to search on. The result is the same for any
+    /// number; only max-n searches are split, and one thread searches as
+    /// the solver always has.
+    pub threads: usize,
+    /// The most memory, in bytes, for the positions the search records,
+    /// shared between the threads
+    pub table_size: usize,
+}
+
+impl Default for SolverOptions {
+    fn default() -> SolverOptions {
+        SolverOptions {
+            mode: SearchMode::MaxN,
+            threads: 1,
+            table_size: 256 << 20,
+        }
+    }
+}
+
+/// The cards each seat wins by the end of the hand when the position given
+/// as for `opt_inner` is played out as `options.mode` assumes.
+#[allow(clippy::too_many_arguments)]
+pub fn solve(
+    options: &SolverOptions,
+    player: Seat,
+    hand: PerSeat<Cards>,
+    won: PerSeat<Cards>,
+    charged: Cards,
+    led_suits: Cards,
+    lead: Option<Card>,
+    trick: Cards,
+    rules: &RuleSet,
+) -> PerSeat<Cards> {
+    let position = Position::new(player, hand, won, led_suits, lead, trick);
+    let limit = SearchLimit::default();
+    match options.mode {
+        SearchMode::MaxN if options.threads > 1 => opt_parallel(&position, options, charged, rules),
+        mode => {
+            let mut search = Search::new(charged, rules, 0, &limit, options.table_size);
+            play_out(&position, mode, &mut search, &mut Vec::new())
+        }
+    }
+}
+
+/// When a search by `solve_limited` must stop.
+#[derive(Clone, Debug, Default)]
+pub struct SearchLimit {
+    /// The time by which to return
+    pub deadline: Option<Instant>,
+    /// Set from elsewhere, usually another thread, to return as soon as
+    /// possible
+    pub cancel: Option<Arc<AtomicBool>>,
+    /// The most positions to search, counting every search made to look
+    /// further ahead, which unlike the time taken is the same on every run
+    pub nodes: Option<u64>,
+}
+
+impl SearchLimit {
+    /// Whether to stop, having searched `nodes` positions. The clock and
+    /// `cancel` are only looked at every so often, as that is slow next to
+    /// searching a position.
+    fn reached(&self, nodes: u64) -> bool {
+        if self.nodes.is_some_and(|max| nodes >= max) {
+            return true;
+        }
+        nodes % 1024 == 0
+            && (self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+                || self
+                    .cancel
+                    .as_ref()
+                    .is_some_and(|cancel| cancel.load(Ordering::Relaxed)))
+    }
+}
+
+/// The best play `solve_limited` found in the time it had.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct LimitedSolve {
+    /// The play for the player to act, or `None` if the hand is over
+    pub play: Option<Card>,
+    /// The cards each seat wins in the line found, up to the tricks looked
+    /// ahead
+    pub won: PerSeat<Cards>,
+    /// The number of tricks the search looked ahead, counting the trick in
+    /// progress; zero if it was stopped before finishing the first
+    pub tricks: usize,
+    /// Whether the search looked ahead to the end of the hand, so that the
+    /// play is the one `solve` would choose
+    pub complete: bool,
+}
+
+/// `solve`, for when there is no time to search to the end of the hand.
+/// The search looks one trick ahead, then two, and so on, until it reaches
+/// the end of the hand or `limit`, and returns the play found by the last
+/// search it finished. A search stopped short of the end of the hand only
+/// counts the cards won by then. Each search starts with the play the last
+/// one found, and they share one table, keyed by how far each looks ahead.
+/// Whatever `options.threads`, the search is on the calling thread.
+#[allow(clippy::too_many_arguments)]
+pub fn solve_limited(
+    options: &SolverOptions,
+    limit: &SearchLimit,
+    player: Seat,
+    hand: PerSeat<Cards>,
+    won: PerSeat<Cards>,
+    charged: Cards,
+    led_suits: Cards,
+    lead: Option<Card>,
+    trick: Cards,
+    rules: &RuleSet,
+) -> LimitedSolve {
+    let position = Position::new(player, hand, won, led_suits, lead, trick);
+    let plays = position.plays(charged, rules);
+    let mut best = LimitedSolve {
+        play: plays.iter().next_back(),
+        won,
+        tricks: 0,
+        complete: plays.is_empty(),
+    };
+    let remaining = position.remaining();
+    let mut search = Search::new(charged, rules, 0, limit, options.table_size);
+    let mut tricks = 1;
+    while !best.complete {
+        search.horizon = remaining.saturating_sub(tricks * rules.players);
+        // the play found looking less far ahead is likely still the best,
+        // and in a paranoid search the better the first play, the more of
+        // the others' lines go unsearched
+        let order = best
+            .play
+            .into_iter()
+            .chain(plays.iter().rev().filter(|&play| Some(play) != best.play));
+        // the results so far, in the order `solve` tries the plays, so
+        // that ties go the same way
+        let mut results = Vec::new();
+        let mut opt: Option<(Card, Points)> = None;
+        for play in order {
+            let next = position.play(play, rules);
+            let points = match options.mode {
+                SearchMode::MaxN => opt_search(&next, &mut search),
+                SearchMode::Paranoid(seat) => {
+                    // nothing worse than the best so far matters, but a play
+                    // `solve` tries before it wins a tie with it, so for
+                    // that play a tie must not be cut off
+                    let (mut alpha, mut beta) = (i32::MIN, i32::MAX);
+                    if let Some((opt_play, opt_points)) = opt {
+                        let opt_money = opt_points.money(charged, seat, rules);
+                        let tie = i32::from(play > opt_play);
+                        if player == seat {
+                            alpha = opt_money - tie;
+                        } else {
+                            beta = opt_money + tie;
+                        }
+                    }
+                    paranoid_search(&next, seat, alpha, beta, &mut search)
+                }
+            };
+            let index = results.partition_point(|&(searched, _)| searched > play);
+            results.insert(index, (play, points));
+            opt = best_for(
+                options.mode,
+                player,
+                results.iter().copied(),
+                charged,
+                rules,
+            );
+        }
+        let mut found = best;
+        if let Some((play, _)) = opt {
+            let next = position.play(play, rules);
+            found.play = Some(play);
+            found.won = play_out(&next, options.mode, &mut search, &mut Vec::new());
+        }
+        if search.stopped {
+            break;
+        }
+        found.tricks = tricks;
+        found.complete = search.horizon == 0;
+        best = found;
+        tricks += 1;
+    }
+    best
+}
+
+/// A solve that shows its working: the line of play it found, and what
+/// each play open to the player to act comes to.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct SolveResult {
+    /// The cards each seat wins by the end of the principal variation
+    pub won: PerSeat<Cards>,
+    /// The plays from the position to the end of the hand, when every seat
+    /// plays as the search assumes
+    pub principal_variation: Vec<(Seat, Card)>,
+    /// The money each seat wins by the end of the hand after each legal
+    /// play by the player to act
+    pub plays: BTreeMap<Card, PerSeat<i32>>,
+}
+
+/// `solve`, with the principal variation and the money each play by the
+/// player to act comes to. The principal variation is the line `solve`
+/// finds. For a paranoid search, every play is searched out, so the money
+/// for the paranoid seat is exact after each, and the principal variation
+/// is a line that wins the seat what it can guarantee. Whatever
+/// `options.threads`, the search is on the calling thread.
+#[allow(clippy::too_many_arguments)]
+pub fn solve_detailed(
+    options: &SolverOptions,
+    player: Seat,
+    hand: PerSeat<Cards>,
+    won: PerSeat<Cards>,
+    charged: Cards,
+    led_suits: Cards,
+    lead: Option<Card>,
+    trick: Cards,
+    rules: &RuleSet,
+) -> SolveResult {
+    let limit = SearchLimit::default();
+    let mut search = Search::new(charged, rules, 0, &limit, options.table_size);
+    let position = Position::new(player, hand, won, led_suits, lead, trick);
+
+    let distinct = position.plays(charged, rules);
+    let results: Vec<_> = distinct
+        .iter()
+        .rev()
+        .map(|play| {
+            (
+                play,
+                full_search(&position.play(play, rules), options.mode, &mut search),
+            )
+        })
+        .collect();
+    let mut plays = BTreeMap::new();
+    for play in position.legal_plays(charged, rules) {
+        // each card stands for the equivalent cards below it
+        let equivalent = if distinct.contains(play.as_cards()) {
+            play
+        } else {
+            distinct.above(play).min()
+        };
+        let &(_, points) = results
+            .iter()
+            .find(|&&(searched, _)| searched == equivalent)
+            .unwrap();
+        let money = PerSeat::from_fn(rules.players, |seat| points.money(charged, seat, rules));
+        plays.insert(play, money);
+    }
+
+    // the first play is chosen from the results above, and the rest as
+    // `solve` chooses them
+    let mut principal_variation = Vec::new();
+    let won = match best_for(options.mode, player, results, charged, rules) {
+        Some((play, _)) => {
+            principal_variation.push((player, play));
+            let next = position.play(play, rules);
+            play_out(&next, options.mode, &mut search, &mut principal_variation)
+        }
+        None => won,
+    };
+
+    SolveResult {
+        won,
+        principal_variation,
+        plays,
+    }
+}
+
+/// The points each seat wins from `position` as `mode` assumes, searched
+/// out in full.
+fn full_search(position: &Position, mode: SearchMode, search: &mut Search) -> Points {
+    match mode {
+        SearchMode::MaxN => opt_search(position, search),
+        SearchMode::Paranoid(seat) => paranoid_search(position, seat, i32::MIN, i32::MAX, search),
+    }
+}
+
+/// Plays out `position` to the end of the hand, or to the search's
+/// horizon, as `mode` assumes each seat plays, adding the plays to `line`.
+/// Returns the cards each seat wins. The searches only find the points each
+/// seat wins, so this is how the cards are found.
+fn play_out(
+    position: &Position,
+    mode: SearchMode,
+    search: &mut Search,
+    line: &mut Vec<(Seat, Card)>,
+) -> PerSeat<Cards> {
+    let mut position = *position;
+    while !search.at_end(&position) {
+        let (play, _) =
+            best_play(&position, mode, search).expect("no plays from a position with cards left");
+        line.push((position.player, play));
+        position = position.play(play, search.rules);
+    }
+    position.won
+}
+
+/// The first of the plays from `position`, in the order they are searched,
+/// that is best for the player to act as `mode` assumes, with the points
+/// each seat wins after it; `None` if the player has no cards left.
+fn best_play(position: &Position, mode: SearchMode, search: &mut Search) -> Option<(Card, Points)> {
+    let (charged, rules) = (search.charged, search.rules);
+    let results = position
+        .plays(charged, rules)
+        .iter()
+        .rev()
+        .map(|play| (play, full_search(&position.play(play, rules), mode, search)));
+    best_for(mode, position.player, results, charged, rules)
+}
+
+/// Of the points each seat wins after each of a position's plays, in the
+/// order they are searched, the first that is best for `player` as `mode`
+/// assumes, or `None` if there are none. Under max-n, `player` wants the
+/// most money for itself; in a paranoid search, the paranoid seat wants
+/// the most money for itself, and the other seats the least for it.
+fn best_for<T, I>(
+    mode: SearchMode,
+    player: Seat,
+    results: I,
+    charged: Cards,
+    rules: &RuleSet,
+) -> Option<(T, Points)>
+where
+    I: IntoIterator<Item = (T, Points)>,
+{
+    let value = |points: Points| match mode {
+        SearchMode::MaxN => points.money(charged, player, rules),
+        SearchMode::Paranoid(seat) if seat == player => points.money(charged, seat, rules),
+        SearchMode::Paranoid(seat) => -points.money(charged, seat, rules),
+    };
+    let mut opt = None;
+    for (play, points) in results {
+        let resulting_value = value(points);
+        match opt {
+            Some((opt_value, _)) if resulting_value <= opt_value => {}
+            _ => opt = Some((resulting_value, (play, points))),
+        }
+    }
+    opt.map(|(_, result)| result)
+}
+
+/// The state of a single search.
+struct Search<'a> {
+    charged: Cards,
+    rules: &'a RuleSet,
+    table: TranspositionTable,
+    /// The number of cards left at the start of a trick at which to stop
+    /// looking ahead
+    horizon: usize,
+    limit: &'a SearchLimit,
+    nodes: u64,
+    /// Whether the limit was reached, after which the results are useless
+    stopped: bool,
+}
+
+impl<'a> Search<'a> {
+    fn new(
+        charged: Cards,
+        rules: &'a RuleSet,
+        horizon: usize,
+        limit: &'a SearchLimit,
+        table_size: usize,
+    ) -> Search<'a> {
+        Search {
+            charged,
+            rules,
+            table: TranspositionTable::new(table_size),
+            horizon,
+            limit,
+            nodes: 0,
+            stopped: false,
+        }
+    }
+
+    /// Whether `position` is the end of the hand or at the horizon, where
+    /// the search just counts the cards won so far.
+    fn at_end(&self, position: &Position) -> bool {
+        position.hand[position.player].is_empty()
+            || (self.horizon > 0 && position.lead.is_none() && position.remaining() <= self.horizon)
+    }
+
+    /// The key for `position` in the table.
+    fn hash(&self, position: &Position) -> u64 {
+        position.search_hash(self.charged, self.rules) ^ ZOBRIST.horizon(self.horizon)
+    }
+
+    /// Whether to stop searching at `position`, and just count the cards
+    /// won so far.
+    fn cutoff(&mut self, position: &Position) -> bool {
+        if position.hand[position.player].is_empty() {
+            return true;
+        }
+        if self.limit.reached(self.nodes) {
+            self.stopped = true;
+        }
+        self.nodes += 1;
+        self.stopped || self.at_end(position)
+    }
+}
+
+/// The first few plies from a position, down to the positions that are
+/// searched separately.
+enum Split {
+    /// A position to search, by its index among them
+    Leaf(usize),
+    /// A position whose player chooses between the plays after it, in the
+    /// order `opt_search` tries them
+    Node(Seat, Vec<Split>),
+}
+
+impl Split {
+    fn new(
+        position: &Position,
+        depth: usize,
+        charged: Cards,
+        rules: &RuleSet,
+        leaves: &mut Vec<Position>,
+    ) -> Split {
+        if depth == 0 || position.hand[position.player].is_empty() {
+            leaves.push(*position);
+            return Split::Leaf(leaves.len() - 1);
+        }
+        let children = position
+            .plays(charged, rules)
+            .iter()
+            .rev()
+            .map(|play| {
+                Split::new(
+                    &position.play(play, rules),
+                    depth - 1,
+                    charged,
+                    rules,
+                    leaves,
+                )
+            })
+            .collect();
+        Split::Node(position.player, children)
+    }
+
+    /// The result of the position, choosing as `opt_search` does between
+    /// the results of the positions searched.
+    fn merge(&self, results: &[PerSeat<Cards>], charged: Cards, rules: &RuleSet) -> PerSeat<Cards> {
+        match *self {
+            Split::Leaf(index) => results[index],
+            Split::Node(player, ref children) => {
+                let results = children.iter().map(|child| {
+                    let won = child.merge(results, charged, rules);
+                    (won, Points::of(won))
+                });
+                best_for(SearchMode::MaxN, player, results, charged, rules)
+                    .expect("no plays from a position with cards left")
+                    .0
+            }
+        }
+    }
+}
+
+/// `opt_search` split across `threads` threads. The first plies are split
+/// until there are a few positions for each thread, which the threads take
+/// in turn, each keeping one table of its share of `table_size` for all the
+/// positions it searches. The table never changes what a search finds, only how quickly, so merging the results in the order
+/// `opt_search` would have found them gives exactly its result.
+fn opt_parallel(
+    position: &Position,
+    options: &SolverOptions,
+    charged: Cards,
+    rules: &RuleSet,
+) -> PerSeat<Cards> {
+    const MAX_DEPTH: usize = 4;
+    let threads = options.threads;
+    let mut leaves = Vec::new();
+    let mut split = Split::new(position, 1, charged, rules, &mut leaves);
+    for depth in 2..=MAX_DEPTH {
+        if leaves.len() >= 4 * threads {
+            break;
+        }
+        leaves.clear();
+        split = Split::new(position, depth, charged, rules, &mut leaves);
+    }
+
+    let limit = SearchLimit::default();
+    let workers = threads.min(leaves.len());
+    let table_size = options.table_size / workers;
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(vec![PerSeat::new(position.hand.players()); leaves.len()]);
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                let mut search = Search::new(charged, rules, 0, &limit, table_size);
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let leaf = match leaves.get(index) {
+                        Some(leaf) => leaf,
+                        None => break,
+                    };
+                    let won = play_out(leaf, SearchMode::MaxN, &mut search, &mut Vec::new());
+                    results.lock().unwrap()[index] = won;
+                }
+            });
+        }
+    });
+    split.merge(&results.into_inner().unwrap(), charged, rules)
+}
+
+/// The max-n search behind `solve`, which looks up and records the
+/// outcome of each position in the search's table, so that a position is
+/// only searched once however many orders of play reach it.
+fn opt_search(position: &Position, search: &mut Search) -> Points {
+    if search.cutoff(position) {
+        return Points::of(position.won);
+    }
+    let hash = search.hash(position);
+    if let Some(outcome) = search.table.get(hash) {
+        return position.resolve(&outcome);
+    }
+
+    let (_, opt_points) = best_play(position, SearchMode::MaxN, search)
+        .expect("no plays from a position with cards left");
+    let outcome = position.outcome(opt_points, Bound::Exact);
+    search.table.insert(hash, position.remaining(), outcome);
+    opt_points
+}
+
+/// The paranoid search behind `solve`: an alpha-beta search of the money
+/// `seat` wins, looking for a result between `alpha` and `beta`. A result
+/// at or outside either is only a bound on the true result, and lines that
+/// cannot change that are not searched.
+fn paranoid_search(
+    position: &Position,
+    seat: Seat,
+    mut alpha: i32,
+    mut beta: i32,
+    search: &mut Search,
+) -> Points {
+    if search.cutoff(position) {
+        return Points::of(position.won);
+    }
+    let (charged, rules) = (search.charged, search.rules);
+    let player = position.player;
+    let hash = search.hash(position);
+    if let Some(outcome) = search.table.get(hash) {
+        let points = position.resolve(&outcome);
+        let result = points.money(charged, seat, rules);
+        match outcome.bound {
+            Bound::Exact => return points,
+            Bound::Lower if result >= beta => return points,
+            Bound::Upper if result <= alpha => return points,
+            _ => {}
+        }
+    }
+
+    let (window_alpha, window_beta) = (alpha, beta);
+    let maximizing = player == seat;
+    let mut opt_money = if maximizing { i32::MIN } else { i32::MAX };
+    let mut opt_points = Points::default();
+    for play in position.plays(charged, rules).iter().rev() {
+        let next = position.play(play, rules);
+        let resulting_points = paranoid_search(&next, seat, alpha, beta, search);
+        let resulting_money = resulting_points.money(charged, seat, rules);
+        if maximizing && resulting_money > opt_money {
+            opt_money = resulting_money;
+            opt_points = resulting_points;
+            alpha = alpha.max(opt_money);
+        } else if !maximizing && resulting_money < opt_money {
+            opt_money = resulting_money;
+            opt_points = resulting_points;
+            beta = beta.min(opt_money);
+        }
+        if alpha >= beta {
+            break;
+        }
+    }
+    let bound = if opt_money <= window_alpha {
+        Bound::Upper
+    } else if opt_money >= window_beta {
+        Bound::Lower
+    } else {
+        Bound::Exact
+    };
+    let outcome = position.outcome(opt_points, bound);
+    search.table.insert(hash, position.remaining(), outcome);
+    opt_points
+}
+
+/// The most positions searched to check a claim. A claim that would take
+/// more is given up on, so that a claim early in the hand cannot hold up
+/// the game.
+const CLAIM_POSITIONS: usize = 100_000;
+
+/// The most memory, in bytes, for the positions a claim search records,
+/// which is room for all of them.
+const CLAIM_TABLE_SIZE: usize = 4 << 20;
+
+/// Whether `claim` by `claimant` holds in every line of play from the
+/// position given as for `opt_inner`: the claimant may choose its plays,
+/// but every defence by the other seats must fail to break the claim.
+/// Returns `None` if there are too many lines of play to check.
+#[allow(clippy::too_many_arguments)]
+pub fn claim_holds(
+    claim: Claim,
+    claimant: Seat,
+    player: Seat,
+    hand: PerSeat<Cards>,
+    won: PerSeat<Cards>,
+    charged: Cards,
+    led_suits: Cards,
+    lead: Option<Card>,
+    trick: Cards,
+    rules: &RuleSet,
+) -> Option<bool> {
+    let position = Position::new(player, hand, won, led_suits, lead, trick);
+    claim_inner(
+        &position,
+        &mut ClaimSearch::new(claim, claimant, hand, charged, rules),
+    )
+}
+
+/// The play for `claimant`, which must be the player to act, that keeps
+/// `claim` holding in every line of play, or `None` if there is none or
+/// there are too many lines of play to check.
+#[allow(clippy::too_many_arguments)]
+pub fn claim_play(
+    claim: Claim,
+    claimant: Seat,
+    hand: PerSeat<Cards>,
+    won: PerSeat<Cards>,
+    charged: Cards,
+    led_suits: Cards,
+    lead: Option<Card>,
+    trick: Cards,
+    rules: &RuleSet,
+) -> Option<Card> {
+    let position = Position::new(claimant, hand, won, led_suits, lead, trick);
+    let mut search = ClaimSearch::new(claim, claimant, hand, charged, rules);
+    for play in position.plays(charged, rules) {
+        if claim_inner(&position.play(play, rules), &mut search)? {
+            return Some(play);
+        }
+    }
+    None
+}
+
+/// The state of a search checking a claim.
+struct ClaimSearch<'a> {
+    claim: Claim,
+    claimant: Seat,
+    /// The cards left to play when the claim was made
+    remaining: Cards,
+    charged: Cards,
+    rules: &'a RuleSet,
+    /// Whether the claim holds from each position searched. Any position
+    /// reached has not broken the claim yet, so whether it holds depends
+    /// only on the play from there.
+    table: TranspositionTable<bool>,
+    positions: usize,
+}
+
+impl<'a> ClaimSearch<'a> {
+    fn new(
+        claim: Claim,
+        claimant: Seat,
+        hand: PerSeat<Cards>,
+        charged: Cards,
+        rules: &'a RuleSet,
+    ) -> ClaimSearch<'a> {
+        ClaimSearch {
+            claim,
+            claimant,
+            remaining: hand.iter().fold(Cards::empty(), |all, &hand| all | hand),
+            charged,
+            rules,
+            table: TranspositionTable::new(CLAIM_TABLE_SIZE),
+            positions: 0,
+        }
+    }
+}
+
+fn claim_inner(position: &Position, search: &mut ClaimSearch) -> Option<bool> {
+    let (claimant, charged, rules) = (search.claimant, search.charged, search.rules);
+    if search
+        .claim
+        .is_broken_by(claimant, search.remaining, position.won, rules)
+    {
+        return Some(false);
+    }
+    if position.hand[position.player].is_empty() {
+        return Some(true);
+    }
+    let hash = position.hash(charged);
+    if let Some(holds) = search.table.get(hash) {
+        return Some(holds);
+    }
+    search.positions += 1;
+    if search.positions > CLAIM_POSITIONS {
+        return None;
+    }
+
+    // the claimant needs one play that holds, a defender one that breaks
+    let claimant_to_play = position.player == claimant;
+    let mut holds = !claimant_to_play;
+    for play in position.plays(charged, rules) {
+        if claim_inner(&position.play(play, rules), search)? == claimant_to_play {
+            holds = claimant_to_play;
+            break;
+        }
+    }
+    search.table.insert(hash, position.remaining(), holds);
+    Some(holds)
+}
+
+/// A position in the play of a hand. The cards played to the trick in
+/// progress stay in their players' hands until the trick is won.
+#[derive(Copy, Clone)]
+struct Position {
+    player: Seat,
+    hand: PerSeat<Cards>,
+    won: PerSeat<Cards>,
+    led_suits: Cards,
+    lead: Option<Card>,
+    trick: Cards,
+    /// The Zobrist hash of the position, leaving out the charged cards,
+    /// which are kept up to date as cards are played
+    hash: u64,
+    /// The part of `hash` for the cards won
+    won_hash: u64,
+    /// The part of `hash` for each of `SUITS`
+    suit_hashes: [u64; 4],
+}
+
+impl Position {
+    fn new(
+        player: Seat,
+        hand: PerSeat<Cards>,
+        won: PerSeat<Cards>,
+        led_suits: Cards,
+        lead: Option<Card>,
+        trick: Cards,
+    ) -> Position {
+        let suit_hashes = SUITS.map(|suit| ZOBRIST.suit(suit, hand, lead, trick));
+        let hash = ZOBRIST.position(player, hand, won, Cards::empty(), led_suits, lead, trick);
+        let won_hash = won
+            .seats()
+            .fold(0, |hash, seat| hash ^ ZOBRIST.won(seat, won[seat]));
+        Position {
+            player,
+            hand,
+            won,
+            led_suits,
+            lead,
+            trick,
+            hash,
+            won_hash,
+            suit_hashes,
+        }
+    }
+
+    /// The Zobrist hash of the position.
+    fn hash(&self, charged: Cards) -> u64 {
+        self.hash ^ ZOBRIST.charged(charged)
+    }
+
+    /// The hash of the position for the search tables. Once no seat can
+    /// shoot the moon and the ten of clubs can no longer change hands, the
+    /// points still to come add to those already won the same whoever has
+    /// them, so the cards won drop out of the hash but for the ten, and
+    /// positions reached by different tricks share their entries.
+    fn search_hash(&self, charged: Cards, rules: &RuleSet) -> u64 {
+        let takers = self
+            .won
+            .iter()
+            .filter(|won| won.intersects(Cards::HEARTS | Cards::QUEEN_SPADES))
+            .count();
+        let moon = rules.shoot_the_moon && takers < 2;
+        let ten = rules.ten_of_clubs_doubles && !self.played().contains(Cards::TEN_CLUBS);
+        if moon || ten {
+            self.hash(charged)
+        } else {
+            self.hash(charged) ^ self.won_hash ^ ZOBRIST.settled(self.won)
+        }
+    }
+
+    /// Brings the part of the hash for the suit of `card` up to date.
+    fn rehash_suit(&mut self, card: Card) {
+        let index = (card.0 / 16) as usize;
+        let suit_hash = ZOBRIST.suit(card.suit(), self.hand, self.lead, self.trick);
+        self.hash ^= self.suit_hashes[index] ^ suit_hash;
+        self.suit_hashes[index] = suit_hash;
+    }
+
+    /// The outcome of the position, for the transposition table, given the
+    /// points each seat wins by the end of the hand.
+    fn outcome(&self, points: Points, bound: Bound) -> Outcome {
+        Outcome {
+            points: points - Points::of(self.won),
+            bound,
+        }
+    }
+
+    /// The points each seat wins by the end of the hand, given the outcome
+    /// of this or an equivalent position.
+    fn resolve(&self, outcome: &Outcome) -> Points {
+        Points::of(self.won) + outcome.points
+    }
+
+    /// The number of cards left in the seats' hands, including those played
+    /// to the trick in progress.
+    fn remaining(&self) -> usize {
+        self.hand.iter().map(|hand| hand.len() as usize).sum()
+    }
+
+    /// Every card won so far.
+    fn played(&self) -> Cards {
+        self.won
+            .iter()
+            .fold(Cards::empty(), |played, &won| played | won)
+    }
+
+    /// The legal plays for the player to act.
+    fn legal_plays(&self, charged: Cards, rules: &RuleSet) -> Cards {
+        legal_plays(
+            self.hand[self.player] - self.trick,
+            charged,
+            self.led_suits,
+            self.lead,
+            self.played().intersects(Cards::HEARTS),
+            rules,
+        )
+    }
+
+    /// The legal plays for the player to act, leaving out all but one of
+    /// each set of equivalent cards.
+    fn plays(&self, charged: Cards, rules: &RuleSet) -> Cards {
+        let played = self.played();
+        let plays = self.legal_plays(charged, rules);
+        let lost = if self.trick == Cards::empty() {
+            Cards::empty()
+        } else {
+            self.trick - trick_winner(self.trick, self.lead.unwrap()).as_cards()
+        };
+        distinct_plays(plays, played | lost, charged, rules)
+    }
+
+    /// The position after the player to act plays `play`.
+    fn play(&self, play: Card, rules: &RuleSet) -> Position {
+        let players = self.hand.players();
+        let trick = self.trick | play;
+        let trick_size = self.trick.len() as usize;
+        let last_trick = (self.hand[self.player] - self.trick).len() == 1;
+        let lead = self.lead.unwrap_or(play);
+        let finishes_trick = trick_size == 2 * players - 1
+            || (trick_size == players - 1
+                && (!rules.nines_extend_tricks || last_trick || !is_nined(trick, lead)));
+        let mut next = if finishes_trick {
+            let winner = holder_of(self.hand, trick_winner(trick, lead)).unwrap();
+            let mut hand = self.hand;
+            for seat in hand.seats() {
+                hand[seat] -= trick;
+            }
+            let mut won = self.won;
+            won[winner] |= trick;
+            let mut next = Position {
+                player: winner,
+                hand,
+                won,
+                led_suits: self.led_suits | lead.suit(),
+                lead: None,
+                trick: Cards::empty(),
+                ..*self
+            };
+            let won_hash = ZOBRIST.won(winner, self.won[winner]) ^ ZOBRIST.won(winner, won[winner]);
+            next.hash ^= won_hash;
+            next.won_hash ^= won_hash;
+            if !self.led_suits.contains(lead.suit()) {
+                next.hash ^= ZOBRIST.led_suits(lead.suit());
+            }
+            next
+        } else {
+            Position {
+                player: self.player.left(),
+                lead: Some(lead),
+                trick,
+                ..*self
+            }
+        };
+
+        next.hash ^= ZOBRIST.player(self.player)
+            ^ ZOBRIST.player(next.player)
+            ^ ZOBRIST.trick(self.lead, self.trick)
+            ^ ZOBRIST.trick(next.lead, next.trick);
+        // the card played leaves the live cards of its suit, unless it wins
+        // the trick, when the card it beats leaves instead
+        next.rehash_suit(play);
+        if !lead.suit().contains(play.as_cards()) {
+            next.rehash_suit(lead);
+        }
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, XorShiftRng};
+    use state::{deal_hands_with, deck};
+    use std::time::Duration;
+
+    /// Two tricks from the end, with seat 0 leading the ace of diamonds
+    /// into seat 1's nine.
+    fn opt_endgame(rules: &RuleSet) -> PerSeat<Cards> {
+        let hand = PerSeat::from([
+            Cards::parse("AD 2C").unwrap(),
+            Cards::parse("9D 3C").unwrap(),
+            Cards::parse("JD 4C").unwrap(),
+            Cards::parse("5D KH").unwrap(),
+        ]);
+        let mut won = PerSeat::new(4);
+        won[Seat::new(2, 4)] = Cards::all() - hand.iter().fold(Cards::empty(), |all, &h| all | h);
+        let lead = Cards::parse("AD").unwrap().max();
+        opt_inner(
+            Seat::new(1, 4),
+            hand,
+            won,
+            Cards::empty(),
+            Cards::all(),
+            Some(lead),
+            lead.as_cards(),
+            rules,
+        )
+    }
+
+    #[test]
+    fn test_opt_presets() {
+        for rules in &[RuleSet::classic(), RuleSet::omnibus()] {
+            let won = opt_endgame(rules);
+            assert_eq!(won.as_slice()[0], Cards::parse("AD 9D JD 5D").unwrap());
+            assert!(won.as_slice()[2].contains(Cards::parse("KH 2C 3C 4C").unwrap()));
+        }
+        let won = opt_endgame(&RuleSet::turbo());
+        assert_eq!(
+            won.as_slice()[0],
+            Cards::parse("AD 9D JD 5D KH 2C 3C 4C").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_opt_three_players() {
+        let hand = PerSeat::from([
+            Cards::parse("KS 5H").unwrap(),
+            Cards::parse("AS 6H").unwrap(),
+            Cards::parse("QS 7H").unwrap(),
+        ]);
+        let mut won = PerSeat::new(3);
+        won[Seat::new(1, 3)] = deck(3) - hand.iter().fold(Cards::empty(), |all, &h| all | h);
+        let won = opt_inner(
+            Seat::new(0, 3),
+            hand,
+            won,
+            Cards::empty(),
+            Cards::all(),
+            None,
+            Cards::empty(),
+            &RuleSet::default(),
+        );
+        let won = won.as_slice();
+        assert!(won[0].is_empty());
+        assert!(won[1].contains(Cards::QUEEN_SPADES));
+        assert_eq!(won[0] | won[1] | won[2], deck(3));
+    }
+
+    /// Two tricks from the end, with seat 0 to lead and seat 1 holding
+    /// the spades it does not.
+    fn claim_endgame(hand: [&str; 4], claim: Claim, claimant: usize) -> bool {
+        let hand = PerSeat::from([
+            Cards::parse(hand[0]).unwrap(),
+            Cards::parse(hand[1]).unwrap(),
+            Cards::parse(hand[2]).unwrap(),
+            Cards::parse(hand[3]).unwrap(),
+        ]);
+        let mut won = PerSeat::new(4);
+        won[Seat::new(1, 4)] = Cards::all() - hand.iter().fold(Cards::empty(), |all, &h| all | h);
+        claim_holds(
+            claim,
+            Seat::new(claimant, 4),
+            Seat::new(0, 4),
+            hand,
+            won,
+            Cards::empty(),
+            Cards::all(),
+            None,
+            Cards::empty(),
+            &RuleSet::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_claim_holds() {
+        let hand = ["AS AH", "2S 3H", "3S 4H", "4S 5H"];
+        assert!(claim_endgame(hand, Claim::AllTricks, 0));
+        assert!(!claim_endgame(hand, Claim::NoMorePoints, 0));
+        assert!(!claim_endgame(hand, Claim::AllTricks, 1));
+        assert!(claim_endgame(hand, Claim::NoMorePoints, 1));
+
+        // the ace of hearts takes the hearts, and the ace of spades the
+        // spades, whichever is led first
+        let hand = ["KS AH", "AS 3H", "3S 4H", "4S 5H"];
+        assert!(!claim_endgame(hand, Claim::AllTricks, 0));
+        assert!(!claim_endgame(hand, Claim::NoMorePoints, 0));
+        assert!(!claim_endgame(hand, Claim::AllTricks, 1));
+        assert!(claim_endgame(hand, Claim::NoMorePoints, 1));
+        assert!(claim_endgame(hand, Claim::NoMorePoints, 3));
+
+        // seat 2 can be made to discard a heart on the ace of spades
+        let hand = ["AS 2H", "KS 3H", "4H 5H", "TS 6H"];
+        assert!(!claim_endgame(hand, Claim::NoMorePoints, 0));
+        assert!(claim_endgame(hand, Claim::NoMorePoints, 2));
+
+        // seat 0 must win the ten of clubs, which would double its score
+        let hand = ["AC 2S", "TC 3S", "3C 4S", "4C 5S"];
+        assert!(!claim_endgame(hand, Claim::NoMorePoints, 0));
+    }
+
+    #[test]
+    fn test_claim_play() {
+        let play = |hand: [&str; 4], claim| {
+            let hand = PerSeat::from([
+                Cards::parse(hand[0]).unwrap(),
+                Cards::parse(hand[1]).unwrap(),
+                Cards::parse(hand[2]).unwrap(),
+                Cards::parse(hand[3]).unwrap(),
+            ]);
+            let mut won = PerSeat::new(4);
+            won[Seat::new(1, 4)] =
+                Cards::all() - hand.iter().fold(Cards::empty(), |all, &h| all | h);
+            claim_play(
+                claim,
+                Seat::new(0, 4),
+                hand,
+                won,
+                Cards::empty(),
+                Cards::all(),
+                None,
+                Cards::empty(),
+                &RuleSet::default(),
+            )
+        };
+        let hand = ["AS AH", "2S 3H", "3S 4H", "4S 5H"];
+        let card = play(hand, Claim::AllTricks).unwrap();
+        assert!(Cards::parse("AS AH").unwrap().contains(card.as_cards()));
+        assert_eq!(play(hand, Claim::NoMorePoints), None);
+        assert_eq!(
+            play(["KS AH", "AS 3H", "3S 4H", "4S 5H"], Claim::AllTricks),
+            None
+        );
+    }
+
+    #[test]
+    fn test_opt_every_line_loses() {
+        // seat 0 loses more than a thousand whatever is played
+        let rules = RuleSet {
+            players: 6,
+            ..RuleSet::default()
+        };
+        let seat = |index| Seat::new(index, 6);
+        let hand = PerSeat::from([
+            Cards::parse("3C").unwrap(),
+            Cards::parse("4C").unwrap(),
+            Cards::parse("5C").unwrap(),
+            Cards::parse("6C").unwrap(),
+            Cards::parse("7C").unwrap(),
+            Cards::parse("8C").unwrap(),
+        ]);
+        let mut won = PerSeat::new(6);
+        won[seat(0)] =
+            (Cards::HEARTS - Cards::parse("2H").unwrap()) | Cards::QUEEN_SPADES | Cards::TEN_CLUBS;
+        won[seat(1)] = deck(6) - won[seat(0)] - hand.iter().fold(Cards::empty(), |all, &h| all | h);
+        let charged = rules.chargeable();
+        let opt = opt_inner(
+            seat(0),
+            hand,
+            won,
+            charged,
+            Cards::all(),
+            None,
+            Cards::empty(),
+            &rules,
+        );
+        assert!(money(opt, charged, seat(0), &rules) < -1000);
+        let mut expected = won;
+        expected[seat(5)] |= Cards::parse("876543C").unwrap();
+        assert_eq!(opt, expected);
+        let options = SolverOptions {
+            threads: 2,
+            ..SolverOptions::default()
+        };
+        assert_eq!(
+            solve(
+                &options,
+                seat(0),
+                hand,
+                won,
+                charged,
+                Cards::all(),
+                None,
+                Cards::empty(),
+                &rules
+            ),
+            expected
+        );
+    }
+
+    /// `opt_inner` as it was before the transposition table.
+    fn opt_reference(position: &Position, charged: Cards, rules: &RuleSet) -> PerSeat<Cards> {
+        let player = position.player;
+        if position.hand[player].is_empty() {
+            return position.won;
+        }
+        // the last best in the order of the cards is the first best in the
+        // order they are searched
+        position
+            .plays(charged, rules)
+            .iter()
+            .map(|play| opt_reference(&position.play(play, rules), charged, rules))
+            .max_by_key(|&won| money(won, charged, player, rules))
+            .unwrap()
+    }
+
+    /// The number of positions with cards left to play in the full tree of
+    /// play from `position`.
+    fn positions(position: &Position, charged: Cards, rules: &RuleSet) -> usize {
+        if position.hand[position.player].is_empty() {
+            return 0;
+        }
+        let plays = position.plays(charged, rules).iter();
+        1 + plays
+            .map(|play| positions(&position.play(play, rules), charged, rules))
+            .sum::<usize>()
+    }
+
+    /// Checks the hash of every position in the tree of play from
+    /// `position` against the hash worked out afresh.
+    fn check_hashes(position: &Position, charged: Cards, rules: &RuleSet) {
+        let fresh = ZOBRIST.position(
+            position.player,
+            position.hand,
+            position.won,
+            charged,
+            position.led_suits,
+            position.lead,
+            position.trick,
+        );
+        assert_eq!(position.hash(charged), fresh);
+        let won_hash = position
+            .won
+            .seats()
+            .fold(0, |hash, seat| hash ^ ZOBRIST.won(seat, position.won[seat]));
+        assert_eq!(position.won_hash, won_hash);
+        if !position.hand[position.player].is_empty() {
+            for play in position.plays(charged, rules) {
+                check_hashes(&position.play(play, rules), charged, rules);
+            }
+        }
+    }
+
+    #[test]
+    fn test_position_hash() {
+        for endgame in endgames(&ENDGAME_SIZES, 1) {
+            check_hashes(&endgame.position, endgame.charged, &endgame.rules);
+        }
+    }
+
+    /// What `test_opt_hand` shows in release builds, on hands small enough
+    /// for every build: the table saves searching two thirds or more of the
+    /// positions.
+    #[test]
+    fn test_opt_table_saves_work() {
+        for endgame in endgames(&[(3, 6), (4, 5)], 1) {
+            let limit = SearchLimit::default();
+            let table_size = SolverOptions::default().table_size;
+            let mut search = Search::new(endgame.charged, &endgame.rules, 0, &limit, table_size);
+            opt_search(&endgame.position, &mut search);
+            let full = positions(&endgame.position, endgame.charged, &endgame.rules);
+            assert!(
+                (search.nodes as usize) * 3 < full,
+                "searched {} of {} positions",
+                search.nodes,
+                full
+            );
+        }
+    }
+
+    /// A position to check the solver from.
+    struct Endgame {
+        rules: RuleSet,
+        charged: Cards,
+        position: Position,
+    }
+
+    impl Endgame {
+        fn solve(&self, options: &SolverOptions) -> PerSeat<Cards> {
+            let position = &self.position;
+            solve(
+                options,
+                position.player,
+                position.hand,
+                position.won,
+                self.charged,
+                position.led_suits,
+                position.lead,
+                position.trick,
+                &self.rules,
+            )
+        }
+    }
+
+    /// The last few tricks of `deals` deals for each of `sizes`, given as
+    /// players and tricks, from the start of a trick and from one card into
+    /// it. The deals are seeded, so they are the same on every run.
+    fn endgames(sizes: &[(usize, usize)], deals: usize) -> Vec<Endgame> {
+        let mut rng = XorShiftRng::from_seed([0x193a_6754, 0xa8a7_d469, 0x9783_0e05, 0x113b_a7bb]);
+        let mut endgames = Vec::new();
+        for &(players, tricks) in sizes {
+            let rules = RuleSet {
+                players,
+                ..RuleSet::default()
+            };
+            for _ in 0..deals {
+                let dealt = deal_hands_with(players, &mut rng);
+                let hand =
+                    PerSeat::from_fn(players, |seat| dealt[seat].iter().take(tricks).collect());
+                let won = PerSeat::from_fn(players, |seat| dealt[seat] - hand[seat]);
+                let charged =
+                    rules.chargeable() & (hand[Seat::new(0, players)] | won[Seat::new(1, players)]);
+                let player = Seat::new(players - 1, players);
+                let start = Position::new(player, hand, won, Cards::all(), None, Cards::empty());
+                let lead = start.play(start.plays(charged, &rules).max(), &rules);
+                for &position in &[start, lead] {
+                    endgames.push(Endgame {
+                        rules,
+                        charged,
+                        position,
+                    });
+                }
+            }
+        }
+        endgames
+    }
+
+    /// Every size of table, with as many tricks left as a debug build can
+    /// check quickly.
+    const ENDGAME_SIZES: [(usize, usize); 4] = [(3, 5), (4, 4), (5, 3), (6, 3)];
+
+    #[test]
+    fn test_opt_transpositions() {
+        for endgame in endgames(&ENDGAME_SIZES, 10) {
+            assert_eq!(
+                endgame.solve(&SolverOptions::default()),
+                opt_reference(&endgame.position, endgame.charged, &endgame.rules)
+            );
+        }
+    }
+
+    #[test]
+    fn test_solve_parallel() {
+        for endgame in endgames(&ENDGAME_SIZES, 5) {
+            let single = endgame.solve(&SolverOptions::default());
+            for &threads in &[2, 3, 8] {
+                let options = SolverOptions {
+                    threads,
+                    ..SolverOptions::default()
+                };
+                assert_eq!(endgame.solve(&options), single);
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve_limited() {
+        let unlimited = SearchLimit::default();
+        for endgame in endgames(&[(4, 4)], 5) {
+            let position = &endgame.position;
+            for &mode in &[SearchMode::MaxN, SearchMode::Paranoid(Seat::new(0, 4))] {
+                let options = SolverOptions {
+                    mode,
+                    ..SolverOptions::default()
+                };
+                let limited = solve_limited(
+                    &options,
+                    &unlimited,
+                    position.player,
+                    position.hand,
+                    position.won,
+                    endgame.charged,
+                    position.led_suits,
+                    position.lead,
+                    position.trick,
+                    &endgame.rules,
+                );
+                assert!(limited.complete);
+                assert_eq!(limited.tricks, 4);
+                let legal = position.legal_plays(endgame.charged, &endgame.rules);
+                assert!(legal.contains(limited.play.unwrap().as_cards()));
+                let table_size = options.table_size;
+                let mut search =
+                    Search::new(endgame.charged, &endgame.rules, 0, &unlimited, table_size);
+                let (play, _) = best_play(position, mode, &mut search).unwrap();
+                assert_eq!(limited.play, Some(play));
+                assert_eq!(limited.won, endgame.solve(&options));
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve_limited_stops() {
+        let rules = RuleSet::default();
+        let hand = deal_hands_with(4, &mut XorShiftRng::from_seed([1, 2, 3, 4]));
+        let player = holder_of(hand, Card::TWO_CLUBS).unwrap();
+        let solve_within = |limit: &SearchLimit| {
+            solve_limited(
+                &SolverOptions::default(),
+                limit,
+                player,
+                hand,
+                PerSeat::new(4),
+                Cards::empty(),
+                Cards::empty(),
+                None,
+                Cards::empty(),
+                &rules,
+            )
+        };
+
+        let cancelled = SearchLimit {
+            cancel: Some(Arc::new(AtomicBool::new(true))),
+            ..SearchLimit::default()
+        };
+        let limited = solve_within(&cancelled);
+        assert_eq!(limited.play, Some(Card::TWO_CLUBS));
+        assert_eq!(limited.tricks, 0);
+        assert!(!limited.complete);
+
+        let start = Instant::now();
+        let deadline = SearchLimit {
+            deadline: Some(start + Duration::from_millis(100)),
+            ..SearchLimit::default()
+        };
+        let limited = solve_within(&deadline);
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(limited.play, Some(Card::TWO_CLUBS));
+        assert!(!limited.complete);
+
+        let capped = SearchLimit {
+            nodes: Some(100_000),
+            ..SearchLimit::default()
+        };
+        let limited = solve_within(&capped);
+        assert!(limited.tricks >= 1);
+        assert!(!limited.complete);
+        assert_eq!(solve_within(&capped), limited);
+    }
+
+    #[test]
+    fn test_solve_detailed() {
+        for endgame in endgames(&[(4, 4)], 5) {
+            let (position, charged, rules) = (&endgame.position, endgame.charged, &endgame.rules);
+            for &mode in &[SearchMode::MaxN, SearchMode::Paranoid(Seat::new(0, 4))] {
+                let options = SolverOptions {
+                    mode,
+                    ..SolverOptions::default()
+                };
+                let result = solve_detailed(
+                    &options,
+                    position.player,
+                    position.hand,
+                    position.won,
+                    charged,
+                    position.led_suits,
+                    position.lead,
+                    position.trick,
+                    rules,
+                );
+
+                // every legal play, each valued as if searched alone
+                let legal = position.legal_plays(charged, rules);
+                assert_eq!(result.plays.keys().cloned().collect::<Cards>(), legal);
+                for (&play, money_after) in &result.plays {
+                    let next = Endgame {
+                        position: position.play(play, rules),
+                        ..endgame
+                    };
+                    let solved = next.solve(&options);
+                    let seat = match mode {
+                        SearchMode::MaxN => position.player,
+                        SearchMode::Paranoid(seat) => seat,
+                    };
+                    assert_eq!(money_after[seat], money(solved, charged, seat, rules));
+                }
+
+                // the principal variation plays out to the result
+                let mut line = *position;
+                for &(seat, card) in &result.principal_variation {
+                    assert_eq!(seat, line.player);
+                    assert!(line.legal_plays(charged, rules).contains(card.as_cards()));
+                    line = line.play(card, rules);
+                }
+                assert!(line.hand.iter().all(|hand| hand.is_empty()));
+                assert_eq!(line.won, result.won);
+                assert_eq!(result.won, endgame.solve(&options));
+            }
+        }
+    }
+
+    /// The money `seat` can guarantee from `position`, by plain minimax.
+    fn paranoid_reference(position: &Position, seat: Seat, charged: Cards, rules: &RuleSet) -> i32 {
+        if position.hand[position.player].is_empty() {
+            return money(position.won, charged, seat, rules);
+        }
+        let results = position
+            .plays(charged, rules)
+            .iter()
+            .map(|play| paranoid_reference(&position.play(play, rules), seat, charged, rules));
+        if position.player == seat {
+            results.max().unwrap()
+        } else {
+            results.min().unwrap()
+        }
+    }
+
+    #[test]
+    fn test_paranoid() {
+        for endgame in endgames(&ENDGAME_SIZES, 10) {
+            let (charged, rules) = (endgame.charged, &endgame.rules);
+            for seat in Seat::all(rules.players) {
+                let solve_as = |mode| {
+                    let options = SolverOptions {
+                        mode,
+                        ..SolverOptions::default()
+                    };
+                    money(endgame.solve(&options), charged, seat, rules)
+                };
+                let paranoid = solve_as(SearchMode::Paranoid(seat));
+                assert_eq!(
+                    paranoid,
+                    paranoid_reference(&endgame.position, seat, charged, rules)
+                );
+                assert!(paranoid <= solve_as(SearchMode::MaxN));
+            }
+        }
+    }
+
+    #[test]
+    fn test_paranoid_forces_queen() {
+        let rules = RuleSet::default();
+        let hand = PerSeat::from([
+            Cards::parse("AS 2C").unwrap(),
+            Cards::parse("QS AC").unwrap(),
+            Cards::parse("2S 3C").unwrap(),
+            Cards::parse("3S 4C").unwrap(),
+        ]);
+        let options = SolverOptions {
+            mode: SearchMode::Paranoid(Seat::new(0, 4)),
+            ..SolverOptions::default()
+        };
+        let won = solve(
+            &options,
+            Seat::new(1, 4),
+            hand,
+            PerSeat::new(4),
+            Cards::empty(),
+            Cards::all(),
+            None,
+            Cards::empty(),
+            &rules,
+        );
+        assert!(won[Seat::new(0, 4)].contains(Cards::QUEEN_SPADES));
+    }
+
+    /// The last eight tricks of the deal in `main`, each seat holding its
+    /// eight highest cards and having won the rest, solved within a time
+    /// budget. A whole deal is far beyond an exact search, and even eight
+    /// tricks are too slow outside of release builds, so this only runs in
+    /// those: `cargo test --release`.
+    #[test]
+    #[cfg(not(debug_assertions))]
+    fn test_solve_main_deal() {
+        let dealt = PerSeat::from([
+            Cards::parse("AJT5S J63H 96D A953C").unwrap(),
+            Cards::parse("9732S T92H K7D KT74C").unwrap(),
+            Cards::parse("KQ6S A5H JT542D Q82C").unwrap(),
+            Cards::parse("84S KQ874H AQ83D J6C").unwrap(),
+        ]);
+        let hand = PerSeat::from_fn(4, |seat| dealt[seat].iter().rev().take(8).collect());
+        let won = PerSeat::from_fn(4, |seat| dealt[seat] - hand[seat]);
+        let budget = Duration::from_secs(30);
+        let start = Instant::now();
+        let opt = solve(
+            &SolverOptions::default(),
+            Seat::new(0, 4),
+            hand,
+            won,
+            Cards::empty(),
+            Cards::all(),
+            None,
+            Cards::empty(),
+            &RuleSet::default(),
+        );
+        let elapsed = start.elapsed();
+        assert!(elapsed < budget, "took {:?}", elapsed);
+        assert_eq!(
+            opt.iter().fold(Cards::empty(), |all, &won| all | won),
+            Cards::all()
+        );
+    }
+
+    /// Slow outside of release builds: `cargo test --release -- --ignored`.
+    #[test]
+    #[ignore]
+    fn test_opt_hand() {
+        let opt = opt_hand(
+            PerSeat::from([Cards::SPADES, Cards::HEARTS, Cards::CLUBS, Cards::DIAMONDS]),
+            &RuleSet::default(),
+        );
+        assert_eq!(
+            opt,
+            PerSeat::from([Cards::empty(), Cards::empty(), Cards::all(), Cards::empty()])
+        );
+    }
+}
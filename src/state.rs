@@ -0,0 +1,82 @@
+use rand::{thread_rng, Rng};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use card::{Card, Rank, Suit};
+use cards::Cards;
+use table::PerSeat;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FullState {
+    /// The cards currently in each player's hand
+    pub hand: PerSeat<Cards>,
+    /// The cards each player has won
+    pub won: PerSeat<Cards>,
+    /// The cards that were charged
+    pub charged: Cards,
+    /// The suits that have been led
+    pub led_suits: Cards,
+    /// The card that led the current trick, or empty
+    pub trick_lead: Cards,
+    /// The cards in the current trick
+    pub trick: Cards,
+}
+
+/// The cards dealt out at a table of `players`: the full deck, less as many
+/// of the lowest diamonds and spades as it takes for it to divide evenly.
+pub fn deck(players: usize) -> Cards {
+    let low = [
+        Card::new(Rank::Two, Suit::Diamonds),
+        Card::new(Rank::Two, Suit::Spades),
+        Card::new(Rank::Three, Suit::Diamonds),
+        Card::new(Rank::Three, Suit::Spades),
+    ];
+    let mut deck = Cards::all();
+    for &card in low.iter().take(52 % players) {
+        deck -= card;
+    }
+    deck
+}
+
+/// A random deal at a table of four.
+pub fn deal_hands() -> PerSeat<Cards> {
+    deal_hands_for(4)
+}
+
+/// A random deal at a table of `players`.
+pub fn deal_hands_for(players: usize) -> PerSeat<Cards> {
+    deal_hands_with(players, &mut thread_rng())
+}
+
+/// A deal at a table of `players`, shuffled by `rng`, so that a seeded
+/// `rng` gives the same deal every time.
+pub fn deal_hands_with<R: Rng>(players: usize, rng: &mut R) -> PerSeat<Cards> {
+    let mut cards: Vec<Card> = deck(players).iter().collect();
+    rng.shuffle(&mut cards);
+    let mut hands = cards.chunks(cards.len() / players);
+    PerSeat::from_fn(players, |_| hands.next().unwrap().iter().cloned().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deck() {
+        assert_eq!(deck(4), Cards::all());
+        assert_eq!(deck(3), Cards::all() - Cards::parse("2D").unwrap());
+        assert_eq!(deck(5), Cards::all() - Cards::parse("2D 2S").unwrap());
+        assert_eq!(deck(6), Cards::all() - Cards::parse("32D 32S").unwrap());
+        for players in 3..7 {
+            let hands = deal_hands_for(players);
+            assert_eq!(hands.players(), players);
+            let mut dealt = Cards::empty();
+            for &hand in &hands {
+                assert_eq!(hand.len() as usize, deck(players).len() as usize / players);
+                dealt |= hand;
+            }
+            assert_eq!(dealt, deck(players));
+        }
+    }
+}
@@ -0,0 +1,315 @@
+use std::fmt;
+use std::iter::FusedIterator;
+use std::ops::{Index, IndexMut};
+use std::slice;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// The fewest players a hand can be played with.
+pub const MIN_PLAYERS: usize = 3;
+
+/// The most players a hand can be played with.
+pub const MAX_PLAYERS: usize = 6;
+
+/// A seat at a table of three to six players. Seats are numbered from zero
+/// in play order, so play passes from each seat to the one on its left.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Seat {
+    index: u8,
+    players: u8,
+}
+
+impl Seat {
+    /// Seat `index` at a table of `players`.
+    ///
+    /// Panics if `players` is not between `MIN_PLAYERS` and `MAX_PLAYERS`,
+    /// or if `index` is not less than `players`.
+    pub fn new(index: usize, players: usize) -> Seat {
+        assert!(
+            (MIN_PLAYERS..=MAX_PLAYERS).contains(&players),
+            "{} players is not between {} and {}",
+            players,
+            MIN_PLAYERS,
+            MAX_PLAYERS
+        );
+        assert!(index < players, "no seat {} at {} players", index, players);
+        Seat {
+            index: index as u8,
+            players: players as u8,
+        }
+    }
+
+    /// Every seat at a table of `players`, in play order from seat zero.
+    pub fn all(players: usize) -> Seats {
+        Seat::new(0, players).play_order()
+    }
+
+    pub fn index(self) -> usize {
+        self.index as usize
+    }
+
+    pub fn players(self) -> usize {
+        self.players as usize
+    }
+
+    /// The seat `n` places to the left, wrapping around the table.
+    pub fn to_left(self, n: usize) -> Seat {
+        Seat {
+            index: ((self.index() + n) % self.players()) as u8,
+            players: self.players,
+        }
+    }
+
+    /// The seat that plays after this one.
+    pub fn left(self) -> Seat {
+        self.to_left(1)
+    }
+
+    /// The seat that plays before this one.
+    pub fn right(self) -> Seat {
+        self.to_left(self.players() - 1)
+    }
+
+    /// The seat halfway around the table, or with an odd number of players
+    /// the nearer of the two seats to the left.
+    pub fn across(self) -> Seat {
+        self.to_left(self.players() / 2)
+    }
+
+    /// Every seat in play order, starting with this one.
+    pub fn play_order(self) -> Seats {
+        Seats {
+            next: self,
+            remaining: self.players(),
+        }
+    }
+}
+
+impl fmt::Display for Seat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.index)
+    }
+}
+
+/// An iterator over seats in play order.
+#[derive(Clone, Debug)]
+pub struct Seats {
+    next: Seat,
+    remaining: usize,
+}
+
+impl Iterator for Seats {
+    type Item = Seat;
+
+    fn next(&mut self) -> Option<Seat> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let seat = self.next;
+        self.next = seat.left();
+        self.remaining -= 1;
+        Some(seat)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for Seats {}
+
+impl FusedIterator for Seats {}
+
+/// One value for each seat at a table of three to six players, indexed by
+/// `Seat`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct PerSeat<T> {
+    players: usize,
+    /// The values for each seat; those past `players` are always the default
+    values: [T; MAX_PLAYERS],
+}
+
+impl<T: Copy + Default> PerSeat<T> {
+    /// The default value for each of `players` seats.
+    ///
+    /// Panics if `players` is not between `MIN_PLAYERS` and `MAX_PLAYERS`.
+    pub fn new(players: usize) -> PerSeat<T> {
+        assert!(
+            (MIN_PLAYERS..=MAX_PLAYERS).contains(&players),
+            "{} players is not between {} and {}",
+            players,
+            MIN_PLAYERS,
+            MAX_PLAYERS
+        );
+        PerSeat {
+            players,
+            values: [T::default(); MAX_PLAYERS],
+        }
+    }
+
+    /// One seat for each value, in order.
+    pub fn from_slice(values: &[T]) -> PerSeat<T> {
+        let mut per_seat = PerSeat::new(values.len());
+        per_seat.values[..values.len()].copy_from_slice(values);
+        per_seat
+    }
+
+    /// The value `f` gives for each of `players` seats.
+    pub fn from_fn<F: FnMut(Seat) -> T>(players: usize, mut f: F) -> PerSeat<T> {
+        let mut per_seat = PerSeat::new(players);
+        for seat in Seat::all(players) {
+            per_seat[seat] = f(seat);
+        }
+        per_seat
+    }
+}
+
+impl<T> PerSeat<T> {
+    pub fn players(&self) -> usize {
+        self.players
+    }
+
+    /// Every seat, in play order from seat zero.
+    pub fn seats(&self) -> Seats {
+        Seat::all(self.players)
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.values[..self.players]
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.values[..self.players]
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.as_slice().iter()
+    }
+
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, T> {
+        self.as_mut_slice().iter_mut()
+    }
+}
+
+impl<T: Copy + Default, const N: usize> From<[T; N]> for PerSeat<T> {
+    fn from(values: [T; N]) -> PerSeat<T> {
+        PerSeat::from_slice(&values)
+    }
+}
+
+impl<T> Index<Seat> for PerSeat<T> {
+    type Output = T;
+
+    fn index(&self, seat: Seat) -> &T {
+        assert_eq!(
+            seat.players(),
+            self.players,
+            "seat {} is at another table",
+            seat
+        );
+        &self.values[seat.index()]
+    }
+}
+
+impl<T> IndexMut<Seat> for PerSeat<T> {
+    fn index_mut(&mut self, seat: Seat) -> &mut T {
+        assert_eq!(
+            seat.players(),
+            self.players,
+            "seat {} is at another table",
+            seat
+        );
+        &mut self.values[seat.index()]
+    }
+}
+
+impl<'a, T> IntoIterator for &'a PerSeat<T> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut PerSeat<T> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> slice::IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for PerSeat<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cards::Cards;
+
+    #[test]
+    fn test_seat() {
+        let seat = Seat::new(3, 4);
+        assert_eq!(seat.left(), Seat::new(0, 4));
+        assert_eq!(seat.right(), Seat::new(2, 4));
+        assert_eq!(seat.across(), Seat::new(1, 4));
+        assert_eq!(seat.left().right(), seat);
+        assert_eq!(seat.across().across(), seat);
+        assert_eq!(Seat::new(0, 3).right(), Seat::new(2, 3));
+        assert_eq!(Seat::new(4, 5).across(), Seat::new(1, 5));
+        assert_eq!(seat.to_string(), "3");
+
+        let order: Vec<_> = Seat::new(1, 4).play_order().map(Seat::index).collect();
+        assert_eq!(order, [1, 2, 3, 0]);
+        assert_eq!(Seat::all(6).len(), 6);
+        assert_eq!(Seat::all(5).last(), Some(Seat::new(4, 5)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_seat_out_of_range() {
+        Seat::new(4, 4);
+    }
+
+    #[test]
+    fn test_per_seat() {
+        let mut scores = PerSeat::<i32>::new(3);
+        assert_eq!(scores.players(), 3);
+        assert_eq!(scores.as_slice(), &[0, 0, 0]);
+        scores[Seat::new(2, 3)] = 7;
+        scores[Seat::new(0, 3)] -= 7;
+        assert_eq!(scores, PerSeat::from([-7, 0, 7]));
+        assert_eq!(scores.iter().sum::<i32>(), 0);
+        assert_eq!(format!("{:?}", scores), "[-7, 0, 7]");
+        assert_eq!(
+            PerSeat::from_fn(3, |seat| seat.index() as i32 * 7 - 7),
+            scores
+        );
+
+        let hands = PerSeat::from([Cards::CLUBS, Cards::DIAMONDS, Cards::HEARTS, Cards::SPADES]);
+        assert_eq!(hands.players(), 4);
+        assert_eq!(hands[Seat::new(3, 4)], Cards::SPADES);
+        assert_eq!(hands.seats().len(), 4);
+        assert_ne!(hands, PerSeat::from_slice(&hands.as_slice()[..3]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_per_seat_other_table() {
+        let hands = PerSeat::<Cards>::new(5);
+        let _ = hands[Seat::new(3, 4)];
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_per_seat_too_many_players() {
+        PerSeat::<i32>::new(7);
+    }
+}
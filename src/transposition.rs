@@ -0,0 +1,427 @@
+use std::mem;
+
+use card::Card;
+use cards::Cards;
+use scoring::Points;
+use table::{PerSeat, Seat, MAX_PLAYERS};
+
+/// Random keys for each part of a position in the play, which are xored
+/// together to hash it.
+///
+/// Cards are keyed by where they rank among the cards of their suit still
+/// in play rather than by which cards they are, since once the cards in
+/// between have gone, a seven and a five play the same. The nines and the
+/// cards that can be charged are also keyed by which cards they are, so
+/// that they are only equal to themselves. Of the trick in progress, only
+/// what can still matter is keyed: the card winning it, its points, and
+/// whether it has been nined.
+pub struct Zobrist {
+    hand: [[u64; 64]; MAX_PLAYERS],
+    special: [[u64; 16]; 64],
+    hearts_won: [[u64; 16]; MAX_PLAYERS],
+    won: [[u64; 64]; MAX_PLAYERS],
+    player: [u64; MAX_PLAYERS],
+    charged: [u64; 64],
+    led: [u64; 4],
+    winning: [[u64; 64]; MAX_PLAYERS],
+    trick_size: [u64; 2 * MAX_PLAYERS],
+    trick_hearts: [u64; 2 * MAX_PLAYERS],
+    trick_honours: [u64; 64],
+    nined: u64,
+    settled: u64,
+    horizon: [u64; 64],
+}
+
+/// The keys, the same on every run so that solves are repeatable.
+pub static ZOBRIST: Zobrist = Zobrist::new();
+
+impl Zobrist {
+    const fn new() -> Zobrist {
+        // splitmix64, which is good enough for hashing and works in a
+        // const fn
+        const fn next(state: &mut u64) -> u64 {
+            *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = *state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^ (z >> 31)
+        }
+        const fn fill<const N: usize>(state: &mut u64) -> [u64; N] {
+            let mut keys = [0; N];
+            let mut i = 0;
+            while i < N {
+                keys[i] = next(state);
+                i += 1;
+            }
+            keys
+        }
+
+        let mut state = 0;
+        let mut hand = [[0; 64]; MAX_PLAYERS];
+        let mut hearts_won = [[0; 16]; MAX_PLAYERS];
+        let mut won = [[0; 64]; MAX_PLAYERS];
+        let mut winning = [[0; 64]; MAX_PLAYERS];
+        let mut seat = 0;
+        while seat < MAX_PLAYERS {
+            hand[seat] = fill(&mut state);
+            hearts_won[seat] = fill(&mut state);
+            won[seat] = fill(&mut state);
+            winning[seat] = fill(&mut state);
+            seat += 1;
+        }
+        let mut special = [[0; 16]; 64];
+        let mut card = 0;
+        while card < 64 {
+            special[card] = fill(&mut state);
+            card += 1;
+        }
+        Zobrist {
+            hand,
+            special,
+            hearts_won,
+            won,
+            player: fill(&mut state),
+            charged: fill(&mut state),
+            led: fill(&mut state),
+            winning,
+            trick_size: fill(&mut state),
+            trick_hearts: fill(&mut state),
+            trick_honours: fill(&mut state),
+            nined: next(&mut state),
+            settled: next(&mut state),
+            horizon: fill(&mut state),
+        }
+    }
+
+    /// The hash of a position, given as for `opt_inner`, which is the same
+    /// for any two positions from the same deal that play out alike. It is
+    /// the parts below xored together, so that a change to the position
+    /// only needs the parts it changes.
+    #[allow(clippy::too_many_arguments)]
+    pub fn position(
+        &self,
+        player: Seat,
+        hand: PerSeat<Cards>,
+        won: PerSeat<Cards>,
+        charged: Cards,
+        led_suits: Cards,
+        lead: Option<Card>,
+        trick: Cards,
+    ) -> u64 {
+        let mut hash = self.player(player)
+            ^ self.charged(charged)
+            ^ self.led_suits(led_suits)
+            ^ self.trick(lead, trick);
+        for seat in won.seats() {
+            hash ^= self.won(seat, won[seat]);
+        }
+        for &suit in &SUITS {
+            hash ^= self.suit(suit, hand, lead, trick);
+        }
+        hash
+    }
+
+    /// The part of the hash for the seat to play.
+    pub fn player(&self, player: Seat) -> u64 {
+        self.player[player.index()]
+    }
+
+    /// The part of the hash for the charged cards.
+    pub fn charged(&self, charged: Cards) -> u64 {
+        xor_all(&self.charged, charged)
+    }
+
+    /// The part of the hash for the suits that have been led.
+    pub fn led_suits(&self, led_suits: Cards) -> u64 {
+        SUITS
+            .iter()
+            .zip(&self.led)
+            .filter(|&(&suit, _)| led_suits.contains(suit))
+            .fold(0, |hash, (_, &key)| hash ^ key)
+    }
+
+    /// The part of the hash for the cards `seat` has won: its hearts, and
+    /// the other cards that score.
+    pub fn won(&self, seat: Seat, won: Cards) -> u64 {
+        let hearts = (won & Cards::HEARTS).len();
+        self.hearts_won[seat.index()][hearts as usize]
+            ^ xor_all(&self.won[seat.index()], won & Points::HONOURS)
+    }
+
+    /// What stands in for the parts for the cards won once they can no
+    /// longer change how the rest of the hand is played: only who has won
+    /// the ten of clubs.
+    pub fn settled(&self, won: PerSeat<Cards>) -> u64 {
+        won.seats().fold(self.settled, |hash, seat| {
+            hash ^ self.won(seat, won[seat] & Cards::TEN_CLUBS)
+        })
+    }
+
+    /// The part of the hash for a search that stops looking ahead once
+    /// `horizon` cards are left, or for none if it is zero, since what a
+    /// position comes to depends on how far ahead it is searched.
+    pub fn horizon(&self, horizon: usize) -> u64 {
+        if horizon == 0 {
+            0
+        } else {
+            self.horizon[horizon]
+        }
+    }
+
+    /// The part of the hash for the trick in progress, apart from the card
+    /// winning it.
+    pub fn trick(&self, lead: Option<Card>, trick: Cards) -> u64 {
+        let lead = match lead {
+            Some(lead) => lead,
+            None => return 0,
+        };
+        let mut hash = self.trick_size[trick.len() as usize]
+            ^ self.trick_hearts[(trick & Cards::HEARTS).len() as usize]
+            ^ xor_all(&self.trick_honours, trick & Points::HONOURS);
+        if trick.intersects(Cards::NINES & lead.suit()) {
+            hash ^= self.nined;
+        }
+        hash
+    }
+
+    /// The part of the hash for the cards of `suit` that can still affect
+    /// the play: those yet to be played, and the card winning the trick.
+    /// Each is keyed by where it ranks among them, so this part changes
+    /// only when a card of the suit is played.
+    pub fn suit(&self, suit: Cards, hand: PerSeat<Cards>, lead: Option<Card>, trick: Cards) -> u64 {
+        let special = Cards::CHARGEABLE | Cards::NINES | Cards::TWO_CLUBS;
+        let base = suit.min().0;
+        let remaining = hand.iter().fold(Cards::empty(), |all, &hand| all | hand);
+        let winning = lead
+            .filter(|lead| lead.suit() == suit)
+            .map(|_| (trick & suit).max());
+        let mut live = (remaining & suit) - trick;
+        if let Some(winning) = winning {
+            live |= winning;
+        }
+        // where `card` ranks among the live cards, counting from the base
+        // of the suit
+        let rank = |card: Card| (base + live.below(card).len() as u8) as usize;
+
+        let mut hash = 0;
+        for seat in hand.seats() {
+            let keys = &self.hand[seat.index()];
+            for card in (hand[seat] & suit) - trick {
+                hash ^= keys[rank(card)];
+            }
+        }
+        for card in live & special {
+            hash ^= self.special[card.0 as usize][rank(card) - base as usize];
+        }
+        if let Some(winning) = winning {
+            let winner = hand
+                .seats()
+                .find(|&seat| hand[seat].contains(winning.as_cards()))
+                .unwrap();
+            hash ^= self.winning[winner.index()][rank(winning)];
+        }
+        hash
+    }
+}
+
+/// The suits, in the order the hash keys them.
+pub const SUITS: [Cards; 4] = [Cards::CLUBS, Cards::DIAMONDS, Cards::HEARTS, Cards::SPADES];
+
+fn xor_all(keys: &[u64; 64], cards: Cards) -> u64 {
+    cards
+        .iter()
+        .fold(0, |hash, card| hash ^ keys[card.0 as usize])
+}
+
+/// What happens from a solved position.
+#[derive(Copy, Clone, Default)]
+pub struct Outcome {
+    /// The points each seat goes on to win, counting the trick in progress
+    pub points: Points,
+    /// How the result of this line compares to the result from the
+    /// position
+    pub bound: Bound,
+}
+
+/// How a recorded result compares to the true result of a position, for
+/// searches that stop once they know a line cannot matter.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum Bound {
+    /// The result is the true result
+    #[default]
+    Exact,
+    /// The true result is at least as good for the seat searched for
+    Lower,
+    /// The true result is at most as good for the seat searched for
+    Upper,
+}
+
+/// Solved positions, by Zobrist hash. The hash leaves out the cards
+/// already played, so a table must only be used for positions from a
+/// single deal.
+///
+/// The table is a fixed number of buckets of two slots, picked by the low
+/// bits of the hash. It starts small and doubles as it fills, up to the
+/// size it is given, after which new positions replace old ones: the first
+/// slot of a bucket keeps the position with the most cards left, which
+/// saves the most searching, and the second the latest.
+pub struct TranspositionTable<T = Outcome> {
+    buckets: Vec<[Slot<T>; 2]>,
+    /// The number of slots in use
+    filled: usize,
+    /// The number of buckets at which the table stops growing
+    max_buckets: usize,
+}
+
+#[derive(Copy, Clone, Default)]
+struct Slot<T> {
+    /// The whole hash of the position, to tell it from the others that
+    /// share its bucket
+    key: u64,
+    /// The number of cards left in the position, or zero for an empty slot
+    depth: u8,
+    value: T,
+}
+
+impl<T: Copy + Default> TranspositionTable<T> {
+    /// The fewest buckets in a table, which it starts with.
+    const MIN_BUCKETS: usize = 1 << 10;
+
+    /// A table of at most `bytes` bytes, or the smallest table if that is
+    /// less.
+    pub fn new(bytes: usize) -> TranspositionTable<T> {
+        let fit = bytes / mem::size_of::<[Slot<T>; 2]>();
+        // the largest power of two that fits
+        let max_buckets = fit
+            .checked_ilog2()
+            .map_or(0, |log| 1 << log)
+            .max(Self::MIN_BUCKETS);
+        TranspositionTable {
+            buckets: vec![[Slot::default(); 2]; Self::MIN_BUCKETS],
+            filled: 0,
+            max_buckets,
+        }
+    }
+
+    fn bucket(&self, hash: u64) -> usize {
+        hash as usize & (self.buckets.len() - 1)
+    }
+
+    pub fn get(&self, hash: u64) -> Option<T> {
+        self.buckets[self.bucket(hash)]
+            .iter()
+            .find(|slot| slot.depth > 0 && slot.key == hash)
+            .map(|slot| slot.value)
+    }
+
+    /// Records `value` for the position with `hash` and `depth` cards
+    /// left.
+    pub fn insert(&mut self, hash: u64, depth: usize, value: T) {
+        debug_assert!(depth > 0, "no position to record without cards left");
+        let slot = Slot {
+            key: hash,
+            depth: depth as u8,
+            value,
+        };
+        let index = self.bucket(hash);
+        let bucket = &mut self.buckets[index];
+        let replaced = if bucket[0].key == hash || bucket[0].depth <= slot.depth {
+            &mut bucket[0]
+        } else {
+            &mut bucket[1]
+        };
+        if replaced.depth == 0 {
+            self.filled += 1;
+        }
+        *replaced = slot;
+        if self.filled > self.buckets.len() && self.buckets.len() < self.max_buckets {
+            self.grow();
+        }
+    }
+
+    /// Doubles the number of buckets, moving each position to the bucket
+    /// its hash now picks.
+    fn grow(&mut self) {
+        let buckets = 2 * self.buckets.len();
+        let old = mem::replace(&mut self.buckets, vec![[Slot::default(); 2]; buckets]);
+        self.filled = 0;
+        for slot in old.iter().flatten().filter(|slot| slot.depth > 0) {
+            self.insert(slot.key, slot.depth as usize, slot.value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zobrist_position() {
+        let hash_of = |hand: PerSeat<Cards>, won: PerSeat<Cards>| {
+            ZOBRIST.position(
+                Seat::new(0, 3),
+                hand,
+                won,
+                Cards::QUEEN_SPADES,
+                Cards::all(),
+                None,
+                Cards::empty(),
+            )
+        };
+        let hand = PerSeat::from([
+            Cards::parse("7S 3H").unwrap(),
+            Cards::parse("5S 8H").unwrap(),
+            Cards::parse("QS 4H").unwrap(),
+        ]);
+        let won = PerSeat::from([
+            Cards::parse("6S 2H").unwrap(),
+            Cards::empty(),
+            Cards::empty(),
+        ]);
+        // with the six gone, the five and the seven are alike
+        let alike = PerSeat::from([
+            Cards::parse("6S 3H").unwrap(),
+            Cards::parse("5S 8H").unwrap(),
+            Cards::parse("QS 4H").unwrap(),
+        ]);
+        let alike_won = PerSeat::from([
+            Cards::parse("7S 5H").unwrap(),
+            Cards::empty(),
+            Cards::empty(),
+        ]);
+        assert_eq!(hash_of(hand, won), hash_of(alike, alike_won));
+
+        // but who holds which cards matters
+        let mut other = hand;
+        other.as_mut_slice().swap(0, 1);
+        assert_ne!(hash_of(hand, won), hash_of(other, won));
+    }
+
+    #[test]
+    fn test_table() {
+        let bucket_size = mem::size_of::<[Slot<bool>; 2]>();
+        let max_buckets = 4 * TranspositionTable::<bool>::MIN_BUCKETS;
+        // a little under room for four times the smallest table
+        let mut table = TranspositionTable::new(max_buckets * bucket_size + bucket_size - 1);
+        for hash in 0..100_000 {
+            table.insert(hash, 1 + (hash % 7) as usize, hash % 3 == 0);
+        }
+        assert_eq!(table.buckets.len(), max_buckets);
+        assert_eq!(table.get(99_999), Some(true));
+        assert_eq!(table.get(99_998), Some(false));
+        assert_eq!(table.get(100_000), None);
+
+        // a position with fewer cards left does not push out one with more
+        let mut table = TranspositionTable::new(0);
+        let buckets = table.buckets.len() as u64;
+        table.insert(5, 20, true);
+        table.insert(5 + buckets, 10, false);
+        table.insert(5 + 2 * buckets, 8, false);
+        assert_eq!(table.get(5), Some(true));
+        assert_eq!(table.get(5 + buckets), None);
+        assert_eq!(table.get(5 + 2 * buckets), Some(false));
+        table.insert(5 + 3 * buckets, 24, false);
+        assert_eq!(table.get(5), None);
+        assert_eq!(table.get(5 + 3 * buckets), Some(false));
+    }
+}
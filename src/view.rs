@@ -0,0 +1,247 @@
+use card::Card;
+use cards::Cards;
+use game::{Game, Phase};
+use history::Trick;
+use passing::PassDirection;
+use rules::{legal_plays, RuleSet};
+use state::deck;
+use table::{PerSeat, Seat};
+
+/// What one seat can see of a game: its own hand and pass, and everything
+/// played or charged in the open. A view owns copies of what it holds, so
+/// it gives no way back to the game or to anyone else's hand, and it is
+/// all a bot in that seat should be given.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct PlayerView {
+    seat: Seat,
+    rules: RuleSet,
+    phase: Phase,
+    dealer: Seat,
+    pass_direction: PassDirection,
+    next: Option<Seat>,
+    hand: Cards,
+    passed: Cards,
+    received: Cards,
+    charged: Cards,
+    led_suits: Cards,
+    tricks: Vec<Trick>,
+    current_trick: Vec<(Seat, Card)>,
+}
+
+impl PlayerView {
+    /// The game as `seat` sees it.
+    pub fn new(game: &Game, seat: Seat) -> PlayerView {
+        PlayerView {
+            seat,
+            rules: *game.rules(),
+            phase: game.phase(),
+            dealer: game.dealer(),
+            pass_direction: game.pass_direction(),
+            next: game.next_to_act(),
+            hand: game.state().hand[seat],
+            passed: game.passed(seat),
+            received: game.received(seat),
+            charged: game.state().charged,
+            led_suits: game.state().led_suits,
+            tricks: game.tricks().to_vec(),
+            current_trick: game.current_trick().to_vec(),
+        }
+    }
+
+    /// The seat whose view this is.
+    pub fn seat(&self) -> Seat {
+        self.seat
+    }
+
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    pub fn dealer(&self) -> Seat {
+        self.dealer
+    }
+
+    pub fn pass_direction(&self) -> PassDirection {
+        self.pass_direction
+    }
+
+    /// The seat whose action the game is waiting on; see
+    /// `Game::next_to_act`.
+    pub fn next_to_act(&self) -> Option<Seat> {
+        self.next
+    }
+
+    /// The cards this seat holds.
+    pub fn hand(&self) -> Cards {
+        self.hand
+    }
+
+    /// The cards this seat chose to pass, or empty if it has not chosen yet.
+    pub fn passed(&self) -> Cards {
+        self.passed
+    }
+
+    /// The cards this seat received in the pass, or empty until every seat
+    /// has passed.
+    pub fn received(&self) -> Cards {
+        self.received
+    }
+
+    /// The cards charged by any seat.
+    pub fn charged(&self) -> Cards {
+        self.charged
+    }
+
+    /// The suits that have been led to a completed trick.
+    pub fn led_suits(&self) -> Cards {
+        self.led_suits
+    }
+
+    /// The completed tricks, in the order they were played.
+    pub fn tricks(&self) -> &[Trick] {
+        &self.tricks
+    }
+
+    /// The seats and cards played so far to the trick in progress.
+    pub fn current_trick(&self) -> &[(Seat, Card)] {
+        &self.current_trick
+    }
+
+    /// The cards each seat has won in completed tricks.
+    pub fn won(&self) -> PerSeat<Cards> {
+        let mut won = PerSeat::new(self.rules.players);
+        for trick in &self.tricks {
+            won[trick.winner] |= trick.cards();
+        }
+        won
+    }
+
+    /// Every card that has been played, including to the trick in progress.
+    pub fn played(&self) -> Cards {
+        let tricks = self.tricks.iter().map(Trick::cards);
+        let current = self.current_trick.iter().map(|&(_, card)| card.as_cards());
+        tricks
+            .chain(current)
+            .fold(Cards::empty(), |played, cards| played | cards)
+    }
+
+    /// The cards still held by the other seats, wherever they may be. The
+    /// cards this seat passed are among them until they are played.
+    pub fn unseen(&self) -> Cards {
+        if self.phase == Phase::Dealing {
+            return Cards::empty();
+        }
+        deck(self.rules.players) - self.hand - self.played()
+    }
+
+    /// The cards this seat may legally play, or empty if it is not this
+    /// seat's turn to play.
+    pub fn legal_plays(&self) -> Cards {
+        if self.phase != Phase::Playing || self.next != Some(self.seat) {
+            return Cards::empty();
+        }
+        let lead = self.current_trick.first().map(|&(_, card)| card);
+        let hearts_broken = self
+            .tricks
+            .iter()
+            .any(|trick| trick.cards().intersects(Cards::HEARTS));
+        legal_plays(
+            self.hand,
+            self.charged,
+            self.led_suits,
+            lead,
+            hearts_broken,
+            &self.rules,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game::Action;
+
+    fn seat(index: usize) -> Seat {
+        Seat::new(index, 4)
+    }
+
+    fn hands() -> PerSeat<Cards> {
+        PerSeat::from([
+            Cards::parse("AJT5S J63H 96D A953C").unwrap(),
+            Cards::parse("9732S T92H K7D KT74C").unwrap(),
+            Cards::parse("KQ6S A5H JT542D Q82C").unwrap(),
+            Cards::parse("84S KQ874H AQ83D J6C").unwrap(),
+        ])
+    }
+
+    #[test]
+    fn test_view() {
+        let mut game = Game::new(seat(3), PassDirection::Left, RuleSet::default());
+        assert_eq!(PlayerView::new(&game, seat(0)).unseen(), Cards::empty());
+        game.apply(Action::Deal(hands())).unwrap();
+        for seat in Seat::all(4) {
+            let cards = game.state().hand[seat].iter().take(3).collect();
+            game.apply(Action::Pass { seat, cards }).unwrap();
+        }
+        while game.phase() == Phase::Charging {
+            let seat = game.next_to_act().unwrap();
+            let cards = (game.state().hand[seat] & Cards::QUEEN_SPADES) - game.state().charged;
+            game.apply(Action::Charge { seat, cards }).unwrap();
+        }
+        for _ in 0..6 {
+            let seat = game.next_to_act().unwrap();
+            let card = game.legal_plays().max();
+            game.apply(Action::Play { seat, card }).unwrap();
+        }
+
+        for seat in Seat::all(4) {
+            let view = PlayerView::new(&game, seat);
+            assert_eq!(view.seat(), seat);
+            assert_eq!(view.hand(), game.state().hand[seat]);
+            assert_eq!(view.passed(), game.passed(seat));
+            assert_eq!(view.received(), game.received(seat));
+            assert_eq!(view.charged(), Cards::QUEEN_SPADES);
+            assert_eq!(view.led_suits(), game.state().led_suits);
+            assert_eq!(view.won(), game.state().won);
+            assert_eq!(view.tricks(), game.tricks());
+            assert_eq!(view.current_trick(), game.current_trick());
+            assert_eq!(view.next_to_act(), game.next_to_act());
+            let others = game
+                .state()
+                .hand
+                .seats()
+                .filter(|&other| other != seat)
+                .fold(Cards::empty(), |cards, other| {
+                    cards | game.state().hand[other]
+                });
+            assert_eq!(view.unseen(), others);
+            if view.next_to_act() == Some(seat) {
+                assert_eq!(view.legal_plays(), game.legal_plays());
+            } else {
+                assert_eq!(view.legal_plays(), Cards::empty());
+            }
+        }
+    }
+
+    #[test]
+    fn test_view_hides_other_hands() {
+        let mut swapped = hands();
+        swapped.as_mut_slice().swap(1, 3);
+        let mut game = Game::new(seat(3), PassDirection::Keeper, RuleSet::default());
+        let mut other = Game::new(seat(3), PassDirection::Keeper, RuleSet::default());
+        game.apply(Action::Deal(hands())).unwrap();
+        other.apply(Action::Deal(swapped)).unwrap();
+        assert_eq!(
+            PlayerView::new(&game, seat(0)),
+            PlayerView::new(&other, seat(0))
+        );
+        assert_ne!(
+            PlayerView::new(&game, seat(1)),
+            PlayerView::new(&other, seat(1))
+        );
+    }
+}